@@ -0,0 +1,129 @@
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use rolling_hash::{BaseCount, OneWay, SupportedBaseCount};
+
+const P: u64 = (1 << 61) - 1;
+const SIZES: [usize; 3] = [1_000, 10_000, 100_000];
+const NEEDLE_LEN: usize = 32;
+
+/// Returns a haystack and a needle occurring only at the end of the haystack.
+fn random_input(len: usize) -> (Vec<u64>, Vec<u64>) {
+    let mut rng = StdRng::seed_from_u64(len as u64);
+    let haystack: Vec<u64> = (0..len).map(|_| rng.random_range(0..4)).collect();
+    let needle = haystack[len - NEEDLE_LEN..].to_vec();
+    (haystack, needle)
+}
+
+/// Returns a haystack `0000...0001` and a needle `00...01`, which is the worst case for the naive search.
+fn adversarial_input(len: usize) -> (Vec<u64>, Vec<u64>) {
+    let mut haystack = vec![0; len];
+    haystack[len - 1] = 1;
+    let needle = haystack[len - NEEDLE_LEN..].to_vec();
+    (haystack, needle)
+}
+
+fn hasher<const B: usize>(haystack: &[u64]) -> OneWay<P, B>
+where
+    BaseCount<B>: SupportedBaseCount,
+{
+    OneWay::from_slice_with_bases(haystack, std::array::from_fn(|i| 1_000_003 + i as u64))
+}
+
+fn bench_position(c: &mut Criterion) {
+    for (name, input) in [
+        ("random", random_input as fn(usize) -> _),
+        ("adversarial", adversarial_input),
+    ] {
+        let mut group = c.benchmark_group(format!("position/{name}"));
+        for len in SIZES {
+            let (haystack, needle) = input(len);
+            let hasher = hasher::<2>(&haystack);
+
+            group.bench_with_input(BenchmarkId::new("OneWay", len), &needle, |b, needle| {
+                b.iter(|| hasher.position(black_box(needle)).map(|i| *i))
+            });
+            group.bench_with_input(BenchmarkId::new("naive", len), &needle, |b, needle| {
+                b.iter(|| {
+                    haystack
+                        .windows(needle.len())
+                        .position(|window| window == black_box(needle.as_slice()))
+                })
+            });
+        }
+        group.finish();
+    }
+}
+
+fn bench_hash_of(c: &mut Criterion) {
+    fn bench<const B: usize>(
+        group: &mut criterion::BenchmarkGroup<'_, criterion::measurement::WallTime>,
+    ) where
+        BaseCount<B>: SupportedBaseCount,
+    {
+        let (haystack, _) = random_input(10_000);
+        let hasher = hasher::<B>(&[]);
+        group.bench_with_input(BenchmarkId::from_parameter(B), &haystack, |b, haystack| {
+            b.iter(|| hasher.hash_of(black_box(haystack)))
+        });
+    }
+
+    let mut group = c.benchmark_group("hash_of");
+    bench::<2>(&mut group);
+    bench::<3>(&mut group);
+    bench::<5>(&mut group);
+    bench::<10>(&mut group);
+    group.finish();
+}
+
+/// Compares `hash_of`, which reduces each element, with `contains_run_of`, which hashes its run pre-reduced.
+fn bench_hash_reduced(c: &mut Criterion) {
+    const LEN: usize = 10_000;
+    let mut group = c.benchmark_group("hash_reduced");
+    let value = StdRng::seed_from_u64(0).random::<u64>();
+    let run = vec![value; LEN];
+    // a single window, so `contains_run_of` is dominated by hashing the run
+    let hasher = hasher::<2>(&run);
+    assert!(*hasher.contains_run_of(value, LEN));
+
+    group.bench_with_input("hash_of", &run, |b, run| {
+        b.iter(|| hasher.hash_of(black_box(run)))
+    });
+    group.bench_function("contains_run_of", |b| {
+        b.iter(|| hasher.contains_run_of(black_box(value), LEN))
+    });
+    group.finish();
+}
+
+fn bench_rebuild(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rebuild");
+    for len in SIZES {
+        let (haystack, _) = random_input(len);
+        let built = hasher::<2>(&haystack);
+        group.bench_with_input(BenchmarkId::new("reversed", len), &built, |b, built| {
+            b.iter(|| built.reversed())
+        });
+        group.bench_with_input(
+            BenchmarkId::new("append_slice", len),
+            &haystack,
+            |b, haystack| {
+                b.iter(|| {
+                    let mut rebuilt = hasher::<2>(&[]);
+                    rebuilt.append_slice(haystack);
+                    rebuilt
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_position,
+    bench_hash_of,
+    bench_hash_reduced,
+    bench_rebuild
+);
+criterion_main!(benches);
@@ -0,0 +1,104 @@
+use crate::{BaseCount, OneWay, Prime, SupportedBaseCount, SupportedPrime};
+
+/// Bases shared by multiple [`OneWay`]s.
+///
+/// Hashes are comparable only if they are computed with the same bases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BaseSet<const P: u64, const B: usize>
+where
+    Prime<P>: SupportedPrime,
+    BaseCount<B>: SupportedBaseCount,
+{
+    base: [u64; B],
+}
+
+impl<const P: u64, const B: usize> BaseSet<P, B>
+where
+    Prime<P>: SupportedPrime,
+    BaseCount<B>: SupportedBaseCount,
+{
+    /// Creates a new instance with randomly generated bases.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            base: *OneWay::<P, B>::new().base(),
+        }
+    }
+
+    /// Creates a new instance with specified bases.
+    ///
+    /// # Panics
+    ///
+    /// See [`OneWay::with_base`].
+    #[inline]
+    pub fn with_base(base: [u64; B]) -> Self {
+        Self {
+            base: *OneWay::<P, B>::with_base(base).base(),
+        }
+    }
+
+    /// Returns the shared bases.
+    pub fn base(&self) -> &[u64; B] {
+        &self.base
+    }
+
+    /// Creates a new empty hasher with the shared bases.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*)
+    #[inline]
+    pub fn hasher(&self) -> OneWay<P, B> {
+        OneWay::with_base(self.base)
+    }
+
+    /// Creates a new empty hasher with the shared bases and at least the specified capacity.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*)
+    #[inline]
+    pub fn hasher_with_capacity(&self, capacity: usize) -> OneWay<P, B> {
+        let mut hasher = self.hasher();
+        hasher.reserve(capacity);
+        hasher
+    }
+}
+
+impl<const P: u64, const B: usize> Default for BaseSet<P, B>
+where
+    Prime<P>: SupportedPrime,
+    BaseCount<B>: SupportedBaseCount,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const P: u64 = (1 << 61) - 1;
+
+    #[test]
+    fn hashers_share_bases() {
+        let bases = BaseSet::<P, 2>::new();
+        let mut hashers = [
+            bases.hasher(),
+            bases.hasher(),
+            bases.hasher_with_capacity(8),
+        ];
+        hashers[0].append_slice(&[9, 1, 2, 3]);
+        hashers[1].append_slice(&[1, 2, 3, 9, 9]);
+        hashers[2].append_slice(&[9, 9, 1, 2, 3]);
+
+        assert!(hashers.iter().all(|hasher| hasher.base() == bases.base()));
+        assert_eq!(hashers[0].hash_range(1..4), hashers[1].hash_range(0..3));
+        assert_eq!(hashers[1].hash_range(0..3), hashers[2].hash_range(2..5));
+        assert_eq!(
+            hashers[0].hash_of(&[1, 2, 3]),
+            hashers[2].hash_of(&[1, 2, 3])
+        );
+    }
+}
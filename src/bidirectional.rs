@@ -0,0 +1,231 @@
+use std::ops::Range;
+
+use crate::{BaseCount, Maybe, OneWay, Prime, SupportedBaseCount, SupportedPrime};
+
+/// Hashes a sequence in both directions, which enables palindrome checks.
+///
+/// Unlike [`OneWay`], elements cannot be appended.
+#[derive(Clone)]
+pub struct BidirectionalRollingHash<const P: u64, const B: usize>
+where
+    Prime<P>: SupportedPrime,
+    BaseCount<B>: SupportedBaseCount,
+{
+    forward: OneWay<P, B>,
+    reverse: OneWay<P, B>,
+}
+
+impl<const P: u64, const B: usize> BidirectionalRollingHash<P, B>
+where
+    Prime<P>: SupportedPrime,
+    BaseCount<B>: SupportedBaseCount,
+{
+    /// Creates a new instance from `hasher`, sharing bases with it.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN*), where *N* is `hasher.len()`.
+    pub fn new(hasher: &OneWay<P, B>) -> Self {
+        Self {
+            forward: hasher.clone(),
+            reverse: hasher.reversed(),
+        }
+    }
+
+    /// Returns the number of elements in `self`.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.forward.len()
+    }
+
+    /// Returns `true` if `self` has a length of 0, and `false` otherwise.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.forward.is_empty()
+    }
+
+    /// Returns the hasher of the original sequence.
+    pub fn forward(&self) -> &OneWay<P, B> {
+        &self.forward
+    }
+
+    /// Returns the hasher of the reversed sequence.
+    pub fn reverse(&self) -> &OneWay<P, B> {
+        &self.reverse
+    }
+
+    /// Returns the hash of the sub slice in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B* log *M*), where *M* is `range.len()`.
+    #[inline]
+    pub fn hash_range(&self, range: Range<usize>) -> [u64; B] {
+        self.forward.hash_range(range)
+    }
+
+    /// Returns the hash of the reversed sub slice in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B* log *M*), where *M* is `range.len()`.
+    pub fn reverse_hash_range(&self, range: Range<usize>) -> [u64; B] {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "range out of bounds"
+        );
+        self.reverse
+            .hash_range(self.len() - range.end..self.len() - range.start)
+    }
+
+    /// Returns `true` if the sub slice in `range` is a palindrome.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B* log *M*), where *M* is `range.len()`.
+    pub fn is_palindrome(&self, range: Range<usize>) -> Maybe<bool> {
+        Maybe::new(self.hash_range(range.clone()) == self.reverse_hash_range(range))
+    }
+
+    /// Returns an iterator over pairs of the start index and the canonical hash of all k-mers, that is, windows of length `k`.
+    ///
+    /// The canonical hash is the minimum of the hashes of the k-mer and its reverse,
+    /// so a k-mer and its reverse are mapped to the same hash.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is `0`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*)
+    pub fn canonical_kmers(
+        &self,
+        k: usize,
+    ) -> impl DoubleEndedIterator<Item = (usize, [u64; B])> + ExactSizeIterator {
+        // the reverse of the `i`-th window is the `(n - k - i)`-th window of the reversed sequence
+        self.forward
+            .windows(k)
+            .zip(self.reverse.windows(k).rev())
+            .map(|(forward, reverse)| forward.min(reverse))
+            .enumerate()
+    }
+
+    /// Counts non-empty palindromic sub slices.
+    ///
+    /// For each center, the longest palindrome is found by binary search on its radius.
+    /// Both odd and even length palindromes are counted.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN* log *N*), where *N* is `self.len()`.
+    pub fn count_palindromes(&self) -> Maybe<usize> {
+        let n = self.len();
+        let base_pows = self.forward.base_pows(n);
+        let is_palindrome = |range: Range<usize>| {
+            let base_pow_len = &base_pows[range.len()];
+            self.forward.hash_range_with(range.clone(), base_pow_len)
+                == self
+                    .reverse
+                    .hash_range_with(n - range.end..n - range.start, base_pow_len)
+        };
+
+        let mut count = 0;
+        for center in 0..2 * n {
+            // palindromes in `center / 2 - radius..(center + 1) / 2 + radius`
+            let (start, end) = (center / 2, center.div_ceil(2));
+            let (mut ok, mut ng) = (0, start.min(n - end) + 1);
+            while ng - ok > 1 {
+                let mid = ok + (ng - ok) / 2;
+                if is_palindrome(start - mid..end + mid) {
+                    ok = mid;
+                } else {
+                    ng = mid;
+                }
+            }
+            // the empty slice is not counted
+            count += if start == end { ok } else { ok + 1 };
+        }
+        Maybe::new(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const P: u64 = (1 << 61) - 1;
+
+    fn build(slice: &[u8]) -> BidirectionalRollingHash<P, 2> {
+        BidirectionalRollingHash::new(&OneWay::from_slice_with_bases(
+            slice,
+            [1_000_003, 998_244_353],
+        ))
+    }
+
+    #[test]
+    fn new_builds_both_directions() {
+        let hasher = build(b"abcd");
+        assert_eq!(hasher.len(), 4);
+        assert_eq!(
+            hasher.forward().as_hashes(),
+            OneWay::<P, 2>::from_slice_with_bases(b"abcd", *hasher.forward().base()).as_hashes()
+        );
+        assert_eq!(
+            hasher.reverse().as_hashes(),
+            hasher.forward().reversed().as_hashes()
+        );
+        assert_eq!(
+            hasher.reverse_hash_range(1..3),
+            hasher.forward().hash_of(b"cb")
+        );
+        assert!(build(b"").is_empty());
+    }
+
+    #[test]
+    fn count_palindromes_matches_brute_force() {
+        // a, a, b, a, a, aa, aa, aba, aabaa
+        assert_eq!(*build(b"aabaa").count_palindromes(), 9);
+        assert_eq!(*build(b"").count_palindromes(), 0);
+
+        for slice in [&b"abba"[..], b"aaaa", b"abcba", b"abacdfgdcaba", b"x"] {
+            let brute_force = (0..slice.len())
+                .flat_map(|i| (i + 1..=slice.len()).map(move |j| &slice[i..j]))
+                .filter(|sub| sub.iter().eq(sub.iter().rev()))
+                .count();
+            assert_eq!(*build(slice).count_palindromes(), brute_force);
+        }
+    }
+
+    #[test]
+    fn canonical_kmers_identify_reverses() {
+        // "abc" at 0 and its reverse "cba" at 4, and the palindrome "aba" at 8
+        let hasher = build(b"abcxcbayaba");
+        let canonical: Vec<_> = hasher.canonical_kmers(3).collect();
+        assert_eq!(canonical.len(), 9);
+        assert_eq!(canonical[0].1, canonical[4].1);
+        assert_ne!(canonical[0].1, canonical[1].1);
+
+        let forward = hasher.forward().hash_of(b"aba");
+        assert_eq!(canonical[8], (8, forward));
+        assert_eq!(
+            canonical[0].1,
+            hasher
+                .forward()
+                .hash_of(b"abc")
+                .min(hasher.forward().hash_of(b"cba"))
+        );
+    }
+}
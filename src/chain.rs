@@ -0,0 +1,58 @@
+//! Prefix-hash chains, shared by hashers with different lane types.
+use std::ops::Range;
+
+use crate::ModArith;
+
+/// Hashes `next` following `prev`, that is, `prev * base + next` for each base.
+///
+/// # Time complexity
+///
+/// *O*(*B*)
+#[inline]
+pub(crate) fn hash_next<M: ModArith, const B: usize>(
+    base: &[M::Lane; B],
+    prev: &[M::Lane; B],
+    next: M::Lane,
+) -> [M::Lane; B] {
+    std::array::from_fn(|i| M::add_mod(M::mul_mod(prev[i], base[i]), next))
+}
+
+/// Returns `base^len` for each base.
+///
+/// # Panics
+///
+/// Panics if `len` does not fit in `u64`, which is impossible on targets up to 64-bit.
+///
+/// # Time complexity
+///
+/// *O*(*B* log *len*)
+#[inline]
+pub(crate) fn base_pow<M: ModArith, const B: usize>(
+    base: &[M::Lane; B],
+    len: usize,
+) -> [M::Lane; B] {
+    let len = u64::try_from(len).expect("length should fit in u64");
+    std::array::from_fn(|i| M::pow_mod(base[i], len))
+}
+
+/// Returns the hash of the sub slice in `range` from the prefix hashes `hash`, where `base_pow_len` is `base^range.len()`.
+///
+/// # Time complexity
+///
+/// *O*(*B*)
+#[inline]
+pub(crate) fn hash_range_with<M: ModArith, const B: usize>(
+    hash: &[[M::Lane; B]],
+    range: Range<usize>,
+    base_pow_len: &[M::Lane; B],
+) -> [M::Lane; B] {
+    if range.is_empty() {
+        return [M::ZERO; B];
+    }
+    let last = hash[range.end - 1];
+    if range.start == 0 {
+        return last;
+    }
+    let offset = hash[range.start - 1];
+    std::array::from_fn(|i| M::sub_mod(last[i], M::mul_mod(offset[i], base_pow_len[i])))
+}
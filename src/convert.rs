@@ -0,0 +1,115 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+/// Conversion into a residue modulo `P`, which is the unit of hashing.
+pub trait Reduce<const P: u64> {
+    /// Returns `self` modulo `P`, which is in `0..P`.
+    fn reduce(&self) -> u64;
+}
+
+macro_rules! reduce_unsigned_impl {
+    ($( $t:ty ),+) => {$(
+        impl<const P: u64> Reduce<P> for $t {
+            #[inline]
+            fn reduce(&self) -> u64 {
+                *self as u64 % P
+            }
+        }
+    )+};
+}
+reduce_unsigned_impl! { u8, u16, u32, u64, usize, bool }
+
+macro_rules! reduce_signed_impl {
+    ($( $t:ty ),+) => {$(
+        impl<const P: u64> Reduce<P> for $t {
+            #[inline]
+            fn reduce(&self) -> u64 {
+                // P < 2^61 fits in i64
+                (*self as i64).rem_euclid(P as i64) as u64
+            }
+        }
+    )+};
+}
+reduce_signed_impl! { i8, i16, i32, i64, isize }
+
+impl<const P: u64> Reduce<P> for char {
+    #[inline]
+    fn reduce(&self) -> u64 {
+        *self as u64 % P
+    }
+}
+
+/// A whole string is reduced to a single residue, which is useful for sequences of words.
+///
+/// The residue is a polynomial of `byte + 1` with the fixed base `257`, so it is deterministic but not injective for long strings.
+impl<const P: u64> Reduce<P> for str {
+    fn reduce(&self) -> u64 {
+        self.bytes().fold(0, |acc, byte| {
+            ((acc as u128 * 257 + byte as u128 + 1) % P as u128) as u64
+        })
+    }
+}
+
+impl<const P: u64> Reduce<P> for String {
+    #[inline]
+    fn reduce(&self) -> u64 {
+        Reduce::<P>::reduce(self.as_str())
+    }
+}
+
+impl<const P: u64, T> Reduce<P> for &T
+where
+    T: Reduce<P> + ?Sized,
+{
+    #[inline]
+    fn reduce(&self) -> u64 {
+        (**self).reduce()
+    }
+}
+
+/// Reduces `value` modulo `P` through its [`Hash`] implementation.
+///
+/// `value` is hashed with [`DefaultHasher::new`], whose keys are fixed, and then reduced.
+/// This adds a hashing step and is lossy, that is, distinct values may be reduced to the same residue.
+/// The result may change between Rust versions, so it should not be persisted.
+///
+/// # Time complexity
+///
+/// Same as hashing `value`.
+pub fn reduce_hashable<H: Hash + ?Sized, const P: u64>(value: &H) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish() % P
+}
+
+/// An adapter that [reduces](Reduce) any [`Hash`] type by [`reduce_hashable`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Hashed<T>(pub T);
+
+impl<const P: u64, T: Hash> Reduce<P> for Hashed<T> {
+    #[inline]
+    fn reduce(&self) -> u64 {
+        reduce_hashable::<T, P>(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const P: u64 = (1 << 61) - 1;
+
+    #[test]
+    fn equal_strings_reduce_equally() {
+        let (a, b) = (String::from("rolling"), "rolling".to_string());
+        assert_eq!(reduce_hashable::<_, P>(&a), reduce_hashable::<_, P>(&b));
+        assert_eq!(
+            Reduce::<P>::reduce(&Hashed(a.clone())),
+            Reduce::<P>::reduce(&Hashed(b))
+        );
+        assert!(reduce_hashable::<_, P>(&a) < P);
+        assert_ne!(
+            reduce_hashable::<_, P>(&a),
+            reduce_hashable::<_, P>("hashing")
+        );
+    }
+}
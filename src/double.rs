@@ -0,0 +1,274 @@
+use crate::{BaseCount, Maybe, OneWay, Prime, SupportedBaseCount, SupportedPrime};
+
+/// A pair of [`OneWay`]s with independent bases, and possibly different prime numbers.
+///
+/// [`DoubleBase`](crate::DoubleBase) uses the same prime number twice.
+///
+/// A match is reported only if both of them agree, so collisions are much less likely than [`OneWay`].
+pub struct DoubleHash<const P1: u64, const P2: u64, const B: usize>
+where
+    Prime<P1>: SupportedPrime,
+    Prime<P2>: SupportedPrime,
+    BaseCount<B>: SupportedBaseCount,
+{
+    first: OneWay<P1, B>,
+    second: OneWay<P2, B>,
+}
+
+impl<const P1: u64, const P2: u64, const B: usize> DoubleHash<P1, P2, B>
+where
+    Prime<P1>: SupportedPrime,
+    Prime<P2>: SupportedPrime,
+    BaseCount<B>: SupportedBaseCount,
+{
+    /// Creates a new instance.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            first: OneWay::new(),
+            second: OneWay::new(),
+        }
+    }
+
+    /// Creates a new instance with at least the specified capacity.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            first: OneWay::with_capacity(capacity),
+            second: OneWay::with_capacity(capacity),
+        }
+    }
+
+    /// Creates a new instance with specified bases.
+    ///
+    /// # Panics
+    ///
+    /// See [`OneWay::with_base`].
+    pub const fn with_base(first: [u64; B], second: [u64; B]) -> Self {
+        Self {
+            first: OneWay::with_base(first),
+            second: OneWay::with_base(second),
+        }
+    }
+
+    /// Same as [`Vec::reserve`].
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.first.reserve(additional);
+        self.second.reserve(additional);
+    }
+
+    /// Returns the number of elements in `self`.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.first.len()
+    }
+
+    /// Returns `true` if `self` has a length of 0, and `false` otherwise.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.first.is_empty()
+    }
+
+    /// Appends an element to the back of `self`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*)
+    #[inline]
+    pub fn push(&mut self, value: u64) {
+        self.first.push(value);
+        self.second.push(value);
+    }
+
+    /// Moves all the elements of `other` into `self`, leaving `other` empty.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BM*), where *M* is `other.len()`
+    pub fn append(&mut self, other: &mut Vec<u64>) {
+        self.reserve(other.len());
+        for value in other.drain(..) {
+            self.push(value);
+        }
+    }
+
+    /// Hashes `slice` with both of the prime numbers.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BM*), where *M* is `slice.len()`.
+    fn hash_slice(&self, slice: &[u64]) -> ([u64; B], [u64; B]) {
+        (self.first.hash_slice(slice), self.second.hash_slice(slice))
+    }
+
+    /// Iterates over pairs of hashes of the sub slices with length `size`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is `0`.
+    fn windows(
+        &self,
+        size: usize,
+    ) -> impl DoubleEndedIterator<Item = ([u64; B], [u64; B])> + ExactSizeIterator {
+        self.first.windows(size).zip(self.second.windows(size))
+    }
+
+    /// Searches for an sub slice in `self`, returning its index.
+    ///
+    /// An empty `slice` matches at every index in `0..=self.len()`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN*), where *N* is `self.len()`.
+    #[must_use]
+    pub fn position(&self, slice: &[u64]) -> Option<Maybe<usize>> {
+        if slice.is_empty() {
+            return Some(Maybe::certain(0));
+        }
+        let target = self.hash_slice(slice);
+        self.windows(slice.len())
+            .position(|sub_slice| sub_slice == target)
+            .map(Maybe::new)
+    }
+
+    /// Searches for sub slice in `self` from the right, returning its index.
+    ///
+    /// An empty `slice` matches at every index in `0..=self.len()`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN*), where *N* is `self.len()`.
+    #[must_use]
+    pub fn rposition(&self, slice: &[u64]) -> Option<Maybe<usize>> {
+        if slice.is_empty() {
+            return Some(Maybe::certain(self.len()));
+        }
+        let target = self.hash_slice(slice);
+        self.windows(slice.len())
+            .rposition(|sub_slice| sub_slice == target)
+            .map(Maybe::new)
+    }
+
+    /// Searches for sub slice in `self`, returning all indexes.
+    ///
+    /// An empty `slice` matches at every index in `0..=self.len()`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN*), where *N* is `self.len()`.
+    pub fn positions(&self, slice: &[u64]) -> impl Iterator<Item = Maybe<usize>> {
+        // exactly one of them is `Some`
+        let everywhere = slice
+            .is_empty()
+            .then(|| (0..=self.len()).map(Maybe::certain));
+        let matches = (!slice.is_empty()).then(|| {
+            let target = self.hash_slice(slice);
+            self.windows(slice.len())
+                .enumerate()
+                .filter_map(move |(i, sub_slice)| (sub_slice == target).then_some(Maybe::new(i)))
+        });
+        everywhere
+            .into_iter()
+            .flatten()
+            .chain(matches.into_iter().flatten())
+    }
+
+    /// Counts sub slices in `self`.
+    ///
+    /// An empty `slice` is counted `self.len() + 1` times.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN*), where *N* is `self.len()`.
+    pub fn count(&self, slice: &[u64]) -> Maybe<usize> {
+        if slice.is_empty() {
+            return Maybe::certain(self.len() + 1);
+        }
+        let target = self.hash_slice(slice);
+        Maybe::new(
+            self.windows(slice.len())
+                .filter(|sub_slice| sub_slice == &target)
+                .count(),
+        )
+    }
+}
+
+impl<const P1: u64, const P2: u64, const B: usize> Default for DoubleHash<P1, P2, B>
+where
+    Prime<P1>: SupportedPrime,
+    Prime<P2>: SupportedPrime,
+    BaseCount<B>: SupportedBaseCount,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const P1: u64 = (1 << 61) - 1;
+    const P2: u64 = (1 << 58) - 27;
+
+    #[test]
+    fn agrees_with_oneway() {
+        let source: Vec<u64> = b"mississippi".iter().map(|&c| c as u64).collect();
+        let oneway = OneWay::<P1, 2>::from_slice_with_bases(&source, [31, 37]);
+        let mut double = DoubleHash::<P1, P2, 2>::with_base([31, 37], [41, 43]);
+        double.append(&mut source.clone());
+
+        for needle in [&source[1..4], &source[2..4], &source[..1], &[0][..], &[]] {
+            assert_eq!(
+                double.position(needle).map(|i| *i),
+                oneway.position(needle).map(|i| *i)
+            );
+            assert_eq!(
+                double.rposition(needle).map(|i| *i),
+                oneway.rposition(needle).map(|i| *i)
+            );
+            assert_eq!(*double.count(needle), *oneway.count(needle));
+        }
+    }
+
+    #[test]
+    fn rejects_single_prime_collision() {
+        // with equal bases `b`, both `[1, 0]` and `[0, b]` hash to `b` modulo `P1`
+        let b = 1_000_003;
+        let mut double = DoubleHash::<P1, P2, 2>::with_base([b, b], [41, 43]);
+        double.append(&mut vec![1, 0, 0, b]);
+
+        let oneway = OneWay::<P1, 2>::from_slice_with_bases(&[1, 0, 0, b], [b, b]);
+        assert_eq!(oneway.position(&[0, b]).map(|i| *i), Some(0));
+        assert_eq!(double.position(&[0, b]).map(|i| *i), Some(2));
+        assert_eq!(*double.count(&[0, b]), 1);
+    }
+
+    #[test]
+    fn empty_needle_matches_everywhere() {
+        let mut double = DoubleHash::<P1, P2, 2>::with_base([31, 37], [41, 43]);
+        for len in [0, 3] {
+            double.append(&mut vec![1; len - double.len()]);
+            assert_eq!(double.position(&[]).map(|i| *i), Some(0));
+            assert!(double.position(&[]).unwrap().is_certain());
+            assert_eq!(double.rposition(&[]).map(|i| *i), Some(len));
+            assert!(double.positions(&[]).map(|i| *i).eq(0..=len));
+            assert_eq!(*double.count(&[]), len + 1);
+        }
+    }
+
+    #[test]
+    fn double_base_rejects_single_base_collision() {
+        // with base `b`, `[1, 0]` and `[0, b]` both hash to `b`
+        let b = 1_000_003;
+        let single = OneWay::<P1, 2>::from_slice_with_bases(&[1u64, 0], [b, b]);
+        assert_eq!(single.position(&[0, b]).map(|i| *i), Some(0));
+
+        let mut double = crate::DoubleBase::<P1, 2>::with_base([b, b], [31, 37]);
+        double.append(&mut vec![1, 0]);
+        assert_eq!(double.position(&[0, b]).map(|i| *i), None);
+        assert_eq!(*double.count(&[0, b]), 0);
+        assert_eq!(double.position(&[1, 0]).map(|i| *i), Some(0));
+    }
+}
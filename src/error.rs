@@ -0,0 +1,25 @@
+use std::fmt;
+
+/// An error returned by [`OneWay::from_raw`](crate::OneWay::from_raw).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FromRawError {
+    /// The base at `index` is `0`, `1`, `P - 1` or out of `0..P`.
+    InvalidBase { index: usize },
+    /// The prefix hash at `index` is out of `0..P`, or disagrees with the previous one between bases.
+    InconsistentHash { index: usize },
+}
+
+impl fmt::Display for FromRawError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidBase { index } => {
+                write!(f, "invalid base at {index}: base should be in 2..=P - 2")
+            }
+            Self::InconsistentHash { index } => {
+                write!(f, "inconsistent prefix hash at {index}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FromRawError {}
@@ -0,0 +1,102 @@
+use crate::{BaseCount, OneWay, Prime, SupportedBaseCount, SupportedPrime};
+
+/// A running hash of a whole sequence, which keeps only the latest hash.
+///
+/// Unlike [`OneWay`], sub slices cannot be hashed, but only *O*(*B*) memory is used.
+/// The result is the same as [`OneWay::fingerprint`] with the same bases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RollingFingerprint<const P: u64, const B: usize>
+where
+    Prime<P>: SupportedPrime,
+    BaseCount<B>: SupportedBaseCount,
+{
+    base: [u64; B],
+    hash: [u64; B],
+}
+
+impl<const P: u64, const B: usize> RollingFingerprint<P, B>
+where
+    Prime<P>: SupportedPrime,
+    BaseCount<B>: SupportedBaseCount,
+{
+    /// Creates a new instance with randomly generated bases.
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_base(*OneWay::<P, B>::new().base())
+    }
+
+    /// Creates a new instance with specified bases.
+    ///
+    /// # Panics
+    ///
+    /// See [`OneWay::with_base`].
+    #[inline]
+    pub fn with_base(base: [u64; B]) -> Self {
+        Self {
+            base: *OneWay::<P, B>::with_base(base).base(),
+            hash: [0; B],
+        }
+    }
+
+    /// Returns the bases.
+    pub fn base(&self) -> &[u64; B] {
+        &self.base
+    }
+
+    /// Appends an element to the sequence.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*)
+    #[inline]
+    pub fn update(&mut self, value: u64) {
+        let value = value % P;
+        self.hash = std::array::from_fn(|i| {
+            Prime::<P>::add_mod(Prime::<P>::mul_mod(self.hash[i], self.base[i]), value)
+        });
+    }
+
+    /// Returns the hash of the whole sequence, or `[0; B]` if no elements are appended.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*)
+    #[inline]
+    pub fn finish(&self) -> [u64; B] {
+        self.hash
+    }
+}
+
+impl<const P: u64, const B: usize> Default for RollingFingerprint<P, B>
+where
+    Prime<P>: SupportedPrime,
+    BaseCount<B>: SupportedBaseCount,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const P: u64 = (1 << 61) - 1;
+
+    #[test]
+    fn finish_matches_oneway_fingerprint() {
+        let bases = [1_000_003, 998_244_353];
+        let mut fingerprint = RollingFingerprint::<P, 2>::with_base(bases);
+        assert_eq!(
+            fingerprint.finish(),
+            OneWay::<P, 2>::with_base(bases).fingerprint()
+        );
+
+        let input = [P + 1, 0, 42, u64::MAX, 7];
+        for value in input {
+            fingerprint.update(value);
+        }
+        let oneway = OneWay::<P, 2>::from_slice_with_bases(&input, bases);
+        assert_eq!(fingerprint.finish(), oneway.fingerprint());
+    }
+}
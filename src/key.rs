@@ -0,0 +1,151 @@
+use std::hash::{Hash, Hasher};
+
+use crate::{BaseCount, OneWay, Prime, SupportedBaseCount, SupportedPrime};
+
+/// A hash of a window, usable as a key of [`HashMap`](std::collections::HashMap) and [`HashSet`](std::collections::HashSet).
+///
+/// Since each lane is already uniformly distributed, all lanes are folded into a single `u64` before being fed to the [`Hasher`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowKey<const B: usize>(pub [u64; B]);
+
+impl<const B: usize> Hash for WindowKey<B> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(fold_lanes(&self.0));
+    }
+}
+
+/// Folds all lanes of a hash into a single `u64`.
+///
+/// # Time complexity
+///
+/// *O*(*B*)
+#[inline]
+pub(crate) fn fold_lanes<const B: usize>(hash: &[u64; B]) -> u64 {
+    // an odd constant derived from the golden ratio
+    const K: u64 = 0x9e37_79b9_7f4a_7c15;
+    hash.iter()
+        .fold(0, |acc, &lane| acc.wrapping_mul(K).wrapping_add(lane))
+}
+
+impl<const B: usize> From<[u64; B]> for WindowKey<B> {
+    #[inline]
+    fn from(value: [u64; B]) -> Self {
+        Self(value)
+    }
+}
+
+/// A whole-sequence hasher, usable as a key of [`HashMap`](std::collections::HashMap) and [`HashSet`](std::collections::HashSet).
+///
+/// Keys are compared by bases, length and [fingerprint](OneWay::fingerprint), not by all the prefix hashes.
+/// Equal sequences are treated as one key only if the hashers share bases, e.g. by [`BaseSet`](crate::BaseSet).
+/// Distinct sequences may be treated as one key due to hash collisions.
+#[derive(Clone)]
+pub struct Fingerprinted<const P: u64, const B: usize>(pub OneWay<P, B>)
+where
+    Prime<P>: SupportedPrime,
+    BaseCount<B>: SupportedBaseCount;
+
+impl<const P: u64, const B: usize> PartialEq for Fingerprinted<P, B>
+where
+    Prime<P>: SupportedPrime,
+    BaseCount<B>: SupportedBaseCount,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.0.bases_compatible(&other.0)
+            && self.0.len() == other.0.len()
+            && self.0.fingerprint() == other.0.fingerprint()
+    }
+}
+
+impl<const P: u64, const B: usize> Eq for Fingerprinted<P, B>
+where
+    Prime<P>: SupportedPrime,
+    BaseCount<B>: SupportedBaseCount,
+{
+}
+
+impl<const P: u64, const B: usize> Hash for Fingerprinted<P, B>
+where
+    Prime<P>: SupportedPrime,
+    BaseCount<B>: SupportedBaseCount,
+{
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.0.len());
+        state.write_u64(self.0.fingerprint_u64());
+    }
+}
+
+impl<const P: u64, const B: usize> From<OneWay<P, B>> for Fingerprinted<P, B>
+where
+    Prime<P>: SupportedPrime,
+    BaseCount<B>: SupportedBaseCount,
+{
+    #[inline]
+    fn from(value: OneWay<P, B>) -> Self {
+        Self(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{HashMap, HashSet},
+        hash::{BuildHasher, BuildHasherDefault, DefaultHasher},
+    };
+
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    #[test]
+    fn folded_lanes_do_not_collide() {
+        let mut rng = StdRng::seed_from_u64(329);
+        let hashes: HashSet<[u64; 3]> = (0..10_000)
+            .map(|_| std::array::from_fn(|_| rng.random_range(0..(1 << 61) - 1)))
+            .collect();
+        // a few small and structured ones, which differ only in a single lane
+        let hashes: Vec<_> = hashes
+            .into_iter()
+            .chain((0..100).flat_map(|i| [[i, 0, 0], [0, i, 0], [0, 0, i]]))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+
+        let folded: HashSet<_> = hashes.iter().map(fold_lanes).collect();
+        assert_eq!(folded.len(), hashes.len());
+
+        // a key feeds exactly its folded lanes to the hasher
+        let state = BuildHasherDefault::<DefaultHasher>::default();
+        for hash in &hashes[..100] {
+            assert_eq!(
+                state.hash_one(WindowKey(*hash)),
+                state.hash_one(fold_lanes(hash))
+            );
+        }
+    }
+
+    #[test]
+    fn equal_sequences_are_one_key() {
+        const P: u64 = (1 << 61) - 1;
+        const BASES: [u64; 2] = [1_000_003, 998_244_353];
+        let document =
+            |text: &[u8]| Fingerprinted(OneWay::<P, 2>::from_slice_with_bases(text, BASES));
+
+        let mut counts = HashMap::new();
+        for text in [&b"same document"[..], b"same document", b"other document"] {
+            *counts.entry(document(text)).or_insert(0) += 1;
+        }
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts[&document(b"same document")], 2);
+        assert_eq!(counts[&document(b"other document")], 1);
+
+        // the same sequence with other bases is another key
+        let other_bases = Fingerprinted(OneWay::<P, 2>::from_slice_with_bases(
+            b"same document",
+            [3, 5],
+        ));
+        assert!(!counts.contains_key(&other_bases));
+    }
+}
@@ -3,19 +3,101 @@
 #![doc = include_str!("../blueprint.md")]
 use std::ops::Deref;
 
+pub mod prelude;
+
 mod prime;
-pub use prime::{PRIMES, Prime, SupportedPrime};
+pub use prime::{ModArith, PRIMES, PRIMES_U32, Prime, PrimeU32, SupportedPrime, SupportedPrimeU32};
+
+mod chain;
+
+mod convert;
+pub use convert::{Hashed, Reduce, reduce_hashable};
+
+mod error;
+pub use error::FromRawError;
 
 mod oneway;
 pub use oneway::OneWay;
 
+/// Former name of [`OneWay`].
+///
+/// # Examples
+///
+/// ```
+/// #![allow(deprecated)]
+/// use rolling_hash::{OneWay, RollingHasher};
+///
+/// let hasher: RollingHasher<{ (1 << 61) - 1 }, 2> = OneWay::from_slice(b"abc");
+/// assert_eq!(hasher.position(b"bc").map(|i| *i), Some(1));
+/// ```
+#[deprecated(note = "use `OneWay` instead")]
+pub type RollingHasher<const P: u64, const B: usize> = OneWay<P, B>;
+
+mod oneway_u32;
+pub use oneway_u32::OneWayU32;
+
+mod double;
+pub use double::DoubleHash;
+
+/// [`DoubleHash`] with the same prime and two independent sets of bases.
+///
+/// A match is reported only if both sets of bases agree, so no second modulus is needed.
+pub type DoubleBase<const P: u64, const B: usize> = DoubleHash<P, P, B>;
+
+mod bidirectional;
+pub use bidirectional::BidirectionalRollingHash;
+
+mod base_set;
+pub use base_set::BaseSet;
+
+mod fingerprint;
+pub use fingerprint::RollingFingerprint;
+
+mod sliding;
+pub use sliding::SlidingWindow;
+
+mod tokens;
+pub use tokens::TokenHasher;
+
+mod multi_prime;
+pub use multi_prime::{PrimeCount, RollingHash, SupportedPrimeCount};
+
+/// [`OneWay`] with the largest supported prime `2^61 - 1` and 2 bases.
+///
+/// # Examples
+///
+/// ```
+/// use rolling_hash::DefaultHasher;
+///
+/// let mut hasher = DefaultHasher::new();
+/// hasher.push(b'a');
+/// assert_eq!(hasher.len(), 1);
+/// ```
+pub type DefaultHasher = OneWay<{ (1 << 61) - 1 }, 2>;
+
+/// [`OneWay`] with the largest supported prime `2^61 - 1` and 3 bases.
+pub type StrongHasher = OneWay<{ (1 << 61) - 1 }, 3>;
+
+/// [`DoubleHash`] with primes `2^61 - 1` and `2^58 - 27` and 2 bases.
+pub type DefaultDoubleHash = DoubleHash<{ (1 << 61) - 1 }, { (1 << 58) - 27 }, 2>;
+
 pub(crate) mod mock;
 pub(crate) use mock::cold_path;
 
-pub(crate) mod windows;
-pub(crate) use windows::Windows;
+#[cfg(test)]
+mod proptests;
+
+mod windows;
+pub use windows::Windows;
+
+mod matches;
+pub use matches::Matches;
 
-/// Specifies the number of bases in [`RollingHasher`].
+mod key;
+pub(crate) use key::fold_lanes;
+pub use key::{Fingerprinted, WindowKey};
+
+/// Specifies the number of bases in [`OneWay`].
 ///
 /// This sill be small.
 pub struct BaseCount<const B: usize>;
@@ -31,12 +113,122 @@ macro_rules! supported_base_count_impl {
 supported_base_count_impl! { 2, 3, 4, 5, 6, 7, 8, 9, 10 }
 
 /// A value that may be incorrect due to hash collisions.
-pub struct Maybe<T>(T);
+///
+/// Results of hash comparisons are uncertain, unless they are [verified](Self::verify).
+#[must_use]
+pub struct Maybe<T> {
+    value: T,
+    certain: bool,
+}
+
+impl<T> Maybe<T> {
+    /// Wraps a value that may be incorrect.
+    pub(crate) const fn new(value: T) -> Self {
+        Self {
+            value,
+            certain: false,
+        }
+    }
+
+    /// Wraps a value that is known to be correct.
+    pub const fn certain(value: T) -> Self {
+        Self {
+            value,
+            certain: true,
+        }
+    }
+
+    /// Returns `true` if the value is known to be correct.
+    pub const fn is_certain(&self) -> bool {
+        self.certain
+    }
+
+    /// Checks the value with `check`, which should not rely on hashes.
+    ///
+    /// Returns the value flagged as certain if `check` returns `true`, and `None` otherwise.
+    pub fn verify(self, check: impl FnOnce(&T) -> bool) -> Option<Self> {
+        check(&self.value).then(|| Self::certain(self.value))
+    }
+
+    /// Passes the value to `f`, which returns another value that may be incorrect.
+    ///
+    /// The result is certain only if both of the values are certain.
+    pub fn and_then<U>(self, f: impl FnOnce(T) -> Maybe<U>) -> Maybe<U> {
+        let result = f(self.value);
+        Maybe {
+            value: result.value,
+            certain: self.certain && result.certain,
+        }
+    }
+
+    /// Returns `None` if `predicate` returns `false` for the value.
+    pub fn filter(self, predicate: impl FnOnce(&T) -> bool) -> Option<Self> {
+        predicate(&self.value).then_some(self)
+    }
+}
+
+impl<T> From<T> for Maybe<T> {
+    fn from(value: T) -> Self {
+        Self::certain(value)
+    }
+}
 
 impl<T> Deref for Maybe<T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const P: u64 = (1 << 61) - 1;
+
+    #[test]
+    fn and_then_chains_lookups() {
+        let hasher = OneWay::<P, 2>::from_slice_with_bases(b"abcabd", [1_000_003, 998_244_353]);
+        let next = hasher
+            .rposition(b"ab")
+            .unwrap()
+            .and_then(|i| hasher.position_after(b"d", i).unwrap());
+        assert_eq!(*next, 5);
+        assert!(!next.is_certain());
+
+        let both_certain = Maybe::certain(1).and_then(|i| Maybe::certain(i + 1));
+        assert_eq!(*both_certain, 2);
+        assert!(both_certain.is_certain());
+        assert!(
+            !Maybe::certain(1)
+                .and_then(|i| Maybe::new(i + 1))
+                .is_certain()
+        );
+
+        assert_eq!(next.filter(|&i| i > 4).map(|i| *i), Some(5));
+        assert!(Maybe::new(5).filter(|&i| i < 5).is_none());
+    }
+
+    #[test]
+    fn from_wraps_a_certain_value() {
+        let maybe = Maybe::from(3usize);
+        assert_eq!(*maybe, 3);
+        assert_eq!(maybe.count_ones(), 2);
+        assert!(maybe.is_certain());
+    }
+
+    #[test]
+    fn verify_flips_certainty() {
+        let hasher = OneWay::<P, 2>::from_slice_with_bases(b"abcabd", [1_000_003, 998_244_353]);
+        let found = hasher.position(b"abd").unwrap();
+        assert!(!found.is_certain());
+
+        let verified = found.verify(|&i| &b"abcabd"[i..i + 3] == b"abd").unwrap();
+        assert_eq!(*verified, 3);
+        assert!(verified.is_certain());
+
+        let rejected = Maybe::new(0).verify(|&i| &b"abcabd"[i..i + 3] == b"abd");
+        assert!(rejected.is_none());
     }
 }
@@ -0,0 +1,121 @@
+use crate::{BaseCount, Maybe, ModArith, Prime, SupportedBaseCount, Windows};
+
+/// An iterator over indexes of a sub slice.
+///
+/// This `struct` is created by [`OneWay::matches`](crate::OneWay::matches) and [`OneWayU32::matches`](crate::OneWayU32::matches).
+pub struct Matches<'a, const P: u64, const B: usize, M = Prime<P>>
+where
+    BaseCount<B>: SupportedBaseCount,
+    M: ModArith,
+{
+    /// `None` if the sub slice is empty, which matches everywhere
+    windows: Option<Windows<'a, P, B, M>>,
+    target: [M::Lane; B],
+
+    /// index of the next window from the front
+    front: usize,
+    /// index of the next window from the back, plus one
+    back: usize,
+}
+
+impl<'a, const P: u64, const B: usize, M> Matches<'a, P, B, M>
+where
+    BaseCount<B>: SupportedBaseCount,
+    M: ModArith,
+{
+    pub(crate) fn new(windows: Windows<'a, P, B, M>, target: [M::Lane; B]) -> Self {
+        let back = windows.len();
+        Self {
+            windows: Some(windows),
+            target,
+            front: 0,
+            back,
+        }
+    }
+
+    /// Creates an iterator yielding `0..count`, which are the indexes of an empty sub slice.
+    pub(crate) fn everywhere(count: usize) -> Self {
+        Self {
+            windows: None,
+            target: [M::ZERO; B],
+            front: 0,
+            back: count,
+        }
+    }
+}
+
+impl<'a, const P: u64, const B: usize, M> Iterator for Matches<'a, P, B, M>
+where
+    BaseCount<B>: SupportedBaseCount,
+    M: ModArith,
+{
+    type Item = Maybe<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Some(windows) = &mut self.windows else {
+            return (self.front < self.back).then(|| {
+                self.front += 1;
+                Maybe::certain(self.front - 1)
+            });
+        };
+        for sub_slice in windows.by_ref() {
+            let i = self.front;
+            self.front += 1;
+            if sub_slice == self.target {
+                return Some(Maybe::new(i));
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.back - self.front))
+    }
+}
+
+impl<'a, const P: u64, const B: usize, M> DoubleEndedIterator for Matches<'a, P, B, M>
+where
+    BaseCount<B>: SupportedBaseCount,
+    M: ModArith,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let Some(windows) = &mut self.windows else {
+            return (self.front < self.back).then(|| {
+                self.back -= 1;
+                Maybe::certain(self.back)
+            });
+        };
+        while let Some(sub_slice) = windows.next_back() {
+            self.back -= 1;
+            if sub_slice == self.target {
+                return Some(Maybe::new(self.back));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::OneWay;
+
+    const P: u64 = (1 << 61) - 1;
+
+    #[test]
+    fn pulls_matches_from_either_end() {
+        let hasher =
+            OneWay::<P, 2>::from_slice_with_bases(b"abcabcabcab", [1_000_003, 998_244_353]);
+        let mut matches = hasher.matches(b"ab");
+        assert_eq!(matches.next().map(|i| *i), Some(0));
+        assert_eq!(matches.next_back().map(|i| *i), Some(9));
+        assert_eq!(matches.next_back().map(|i| *i), Some(6));
+        assert_eq!(matches.next().map(|i| *i), Some(3));
+        assert_eq!(matches.next().map(|i| *i), None);
+        assert_eq!(matches.next_back().map(|i| *i), None);
+
+        let mut everywhere = hasher.matches(b"");
+        assert_eq!(everywhere.next().map(|i| *i), Some(0));
+        assert_eq!(everywhere.next_back().map(|i| *i), Some(11));
+        assert_eq!(everywhere.count(), 10);
+    }
+}
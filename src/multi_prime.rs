@@ -0,0 +1,390 @@
+use std::ops::Range;
+
+use crate::PRIMES;
+
+/// Specified number of prime numbers used by [`RollingHash`].
+pub struct PrimeCount<const N: usize>;
+
+/// A marker trait for supported number of prime numbers.
+pub trait SupportedPrimeCount {}
+
+macro_rules! supported_prime_count_impl {
+    ($( $n:literal ),+) => {$(
+        impl SupportedPrimeCount for PrimeCount<$n> {}
+    )+};
+}
+supported_prime_count_impl! { 1, 2, 3, 4, 5, 6, 7, 8, 9, 10 }
+
+/// Rolling hash with `N` distinct prime numbers, each of which has a single base.
+///
+/// Unlike [`OneWay`](crate::OneWay), prime numbers are chosen at runtime from [`PRIMES`].
+/// A match is reported only if all of them agree.
+#[derive(Clone)]
+pub struct RollingHash<const N: usize>
+where
+    PrimeCount<N>: SupportedPrimeCount,
+{
+    primes: [u64; N],
+    bases: [u64; N],
+    hashed: Vec<[u64; N]>,
+}
+
+impl<const N: usize> RollingHash<N>
+where
+    PrimeCount<N>: SupportedPrimeCount,
+{
+    /// Creates a new instance with the `N` largest prime numbers in [`PRIMES`] and randomly generated bases.
+    pub fn new() -> Self {
+        let primes: [u64; N] = std::array::from_fn(|i| PRIMES[PRIMES.len() - 1 - i]);
+        Self::with_config(
+            primes,
+            std::array::from_fn(|i| rand::random_range(2..=primes[i] - 2)),
+        )
+    }
+
+    /// Creates a new instance with specified prime numbers and bases.
+    ///
+    /// Instances with the same configuration produce comparable hashes, like [`BaseSet`](crate::BaseSet).
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of `primes` is not in [`PRIMES`] or duplicated,
+    /// or if `bases[i]` is not in `2..=primes[i] - 2`.
+    pub fn with_config(primes: [u64; N], bases: [u64; N]) -> Self {
+        for (i, &p) in primes.iter().enumerate() {
+            assert!(
+                PRIMES.contains(&p),
+                "unsupported prime: primes should be in PRIMES"
+            );
+            assert!(
+                !primes[..i].contains(&p),
+                "duplicated prime: primes should be distinct"
+            );
+            assert!(
+                2 <= bases[i] && bases[i] <= p - 2,
+                "invalid base: base should be in 2..=P - 2"
+            );
+        }
+
+        Self {
+            primes,
+            bases,
+            hashed: Vec::new(),
+        }
+    }
+
+    /// Returns the prime numbers.
+    pub fn primes(&self) -> &[u64; N] {
+        &self.primes
+    }
+
+    /// Returns the bases, where `bases()[i]` is for `primes()[i]`.
+    pub fn bases(&self) -> &[u64; N] {
+        &self.bases
+    }
+
+    /// Creates a new instance from `slice`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*NM*), where *M* is `slice.len()`.
+    pub fn from_slice(slice: &[u64]) -> Self {
+        let mut hasher = Self::new();
+        hasher.reserve(slice.len());
+        for &value in slice {
+            hasher.push(value);
+        }
+        hasher
+    }
+
+    /// Same as [`Vec::reserve`].
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.hashed.reserve(additional);
+    }
+
+    /// Returns the number of elements in `self`.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.hashed.len()
+    }
+
+    /// Returns `true` if `self` has a length of 0, and `false` otherwise.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.hashed.is_empty()
+    }
+
+    /// Appends an element to the back of `self`, hashing it with all the prime numbers.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*N*)
+    pub fn push(&mut self, value: u64) {
+        let prev = self.hashed.last().copied().unwrap_or([0; N]);
+        let next = self.hash_next(&prev, value);
+        self.hashed.push(next);
+    }
+
+    /// Moves all the elements of `other` into `self`, leaving `other` empty.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*NM*), where *M* is `other.len()`
+    pub fn append(&mut self, other: &mut Vec<u64>) {
+        self.reserve(other.len());
+        for value in other.drain(..) {
+            self.push(value);
+        }
+    }
+
+    /// Hashes `next` following `prev`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*N*)
+    fn hash_next(&self, prev: &[u64; N], next: u64) -> [u64; N] {
+        std::array::from_fn(|i| {
+            let p = self.primes[i];
+            (mul_mod(prev[i], self.bases[i], p) + next % p) % p
+        })
+    }
+
+    /// Hashes `slice` by using `self`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*NM*), where *M* is `slice.len()`.
+    fn hash_slice(&self, slice: &[u64]) -> [u64; N] {
+        slice
+            .iter()
+            .fold([0; N], |prev, &next| self.hash_next(&prev, next))
+    }
+
+    /// Returns `base^len` for each prime number.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*N* log *len*)
+    fn base_pow(&self, len: usize) -> [u64; N] {
+        let len = u64::try_from(len).expect("length should fit in u64");
+        std::array::from_fn(|i| pow_mod(self.bases[i], len, self.primes[i]))
+    }
+
+    /// Returns the hashes of the sub slice in `range` for all the prime numbers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*N* log *M*), where *M* is `range.len()`.
+    pub fn hash_range(&self, range: Range<usize>) -> [u64; N] {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "range out of bounds"
+        );
+
+        let base_pow_len = self.base_pow(range.len());
+        self.hash_range_with(range, &base_pow_len)
+    }
+
+    /// Returns `true` if the sub slices in `a` and `b` are equal, that is, all the prime numbers agree.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` or `b` is out of bounds.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*N* log *M*), where *M* is `a.len()`.
+    pub fn substring_eq(&self, a: Range<usize>, b: Range<usize>) -> bool {
+        assert!(
+            a.start <= a.end && a.end <= self.len() && b.start <= b.end && b.end <= self.len(),
+            "range out of bounds"
+        );
+
+        if a.len() != b.len() {
+            return false;
+        }
+        let base_pow_len = self.base_pow(a.len());
+        self.hash_range_with(a, &base_pow_len) == self.hash_range_with(b, &base_pow_len)
+    }
+
+    /// Returns the hashes of the sub slice in `range`, where `base_pow_len` is `base^range.len()`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*N*)
+    fn hash_range_with(&self, range: Range<usize>, base_pow_len: &[u64; N]) -> [u64; N] {
+        if range.is_empty() {
+            return [0; N];
+        }
+        let last = self.hashed[range.end - 1];
+        if range.start == 0 {
+            return last;
+        }
+        let offset = self.hashed[range.start - 1];
+        std::array::from_fn(|i| {
+            let p = self.primes[i];
+            (last[i] + p - mul_mod(offset[i], base_pow_len[i], p)) % p
+        })
+    }
+
+    /// Iterates over hashes of the sub slices with length `size`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*N*) per item.
+    fn windows(&self, size: usize) -> impl Iterator<Item = [u64; N]> {
+        let base_pow_size = self.base_pow(size);
+        (0..(self.len() + 1).saturating_sub(size))
+            .map(move |start| self.hash_range_with(start..start + size, &base_pow_size))
+    }
+
+    /// Searches for `sub_slice` in `self`, returning its index.
+    ///
+    /// Unlike [`OneWay::position`](crate::OneWay::position), the result is not wrapped in [`Maybe`](crate::Maybe),
+    /// since a false match requires collisions for all `N` prime numbers at once.
+    /// The risk is still not zero, so compare the sub slices directly if it matters.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*N*(*L* + *M*)), where *L* is `self.len()` and *M* is `sub_slice.len()`.
+    pub fn find(&self, sub_slice: &[u64]) -> Option<usize> {
+        let target = self.hash_slice(sub_slice);
+        self.windows(sub_slice.len())
+            .position(|hash| hash == target)
+    }
+
+    /// Searches for `sub_slice` in `self`, returning all indexes in ascending order.
+    ///
+    /// See [`find`](Self::find) for the residual risk.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*N*(*L* + *M*)), where *L* is `self.len()` and *M* is `sub_slice.len()`.
+    pub fn find_all(&self, sub_slice: &[u64]) -> Vec<usize> {
+        let target = self.hash_slice(sub_slice);
+        self.windows(sub_slice.len())
+            .enumerate()
+            .filter_map(|(i, hash)| (hash == target).then_some(i))
+            .collect()
+    }
+}
+
+impl<const N: usize> Default for RollingHash<N>
+where
+    PrimeCount<N>: SupportedPrimeCount,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Performs `(lhs * rhs) % p` by widening to `u128`.
+///
+/// # Time complexity
+///
+/// *O*(1)
+#[inline]
+const fn mul_mod(lhs: u64, rhs: u64, p: u64) -> u64 {
+    (lhs as u128 * rhs as u128 % p as u128) as u64
+}
+
+/// Performs `value^exp % p`.
+///
+/// # Time complexity
+///
+/// *O*(log *exp*)
+const fn pow_mod(mut value: u64, mut exp: u64, p: u64) -> u64 {
+    let mut result = 1;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, value, p);
+        }
+        exp >>= 1;
+        value = mul_mod(value, value, p);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_and_find_all_on_known_sequence() {
+        let hasher = RollingHash::<3>::from_slice(&[1, 2, 3, 1, 2, 3, 1, 2]);
+        assert_eq!(hasher.find(&[2, 3]), Some(1));
+        assert_eq!(hasher.find_all(&[2, 3]), [1, 4]);
+        assert_eq!(hasher.find_all(&[1, 2]), [0, 3, 6]);
+        assert_eq!(hasher.find(&[3, 2]), None);
+        assert_eq!(hasher.find_all(&[3, 2]), Vec::<usize>::new());
+        assert_eq!(hasher.find(&[1, 2, 3, 1, 2, 3, 1, 2, 3]), None);
+    }
+
+    #[test]
+    fn shared_config_makes_hashes_comparable() {
+        let primes = [PRIMES[9], PRIMES[0]];
+        let bases = [1_000_003, 998_244_353];
+        let mut a = RollingHash::<2>::with_config(primes, bases);
+        let mut b = RollingHash::<2>::with_config(primes, bases);
+        a.append(&mut vec![5, 1, 2, 3, 9]);
+        b.append(&mut vec![1, 2, 3, 7]);
+        assert_eq!(a.hash_range(1..4), b.hash_range(0..3));
+        assert_ne!(a.hash_range(2..5), b.hash_range(1..4));
+        assert_eq!((a.primes(), a.bases()), (&primes, &bases));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid base")]
+    fn with_config_rejects_invalid_base() {
+        let _ = RollingHash::<2>::with_config([PRIMES[9], PRIMES[0]], [2, PRIMES[0] - 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicated prime")]
+    fn with_config_rejects_duplicated_prime() {
+        let _ = RollingHash::<2>::with_config([PRIMES[0], PRIMES[0]], [2, 3]);
+    }
+
+    #[test]
+    fn push_and_append_keep_channels_in_lockstep() {
+        let mut hasher = RollingHash::<4>::new();
+        assert!(hasher.is_empty());
+        hasher.push(1);
+        hasher.push(u64::MAX);
+        let mut rest = vec![3, 4, 5];
+        hasher.append(&mut rest);
+        assert!(rest.is_empty());
+        assert_eq!(hasher.len(), 5);
+        // every prefix has a reduced hash for each of the 4 primes
+        assert!((0..4).all(|i| {
+            hasher
+                .hashed
+                .iter()
+                .all(|hash| hash[i] < hasher.primes()[i])
+        }));
+        assert_eq!(hasher.find_all(&[u64::MAX, 3]), [1]);
+    }
+
+    #[test]
+    fn substring_eq_compares_ranges() {
+        let hasher = RollingHash::<2>::from_slice(&[7, 1, 2, 3, 1, 2, 4]);
+        assert!(hasher.substring_eq(1..3, 4..6));
+        assert!(!hasher.substring_eq(1..4, 4..7));
+        assert!(!hasher.substring_eq(1..3, 4..7));
+        assert!(hasher.substring_eq(2..2, 5..5));
+        assert_eq!(hasher.hash_range(1..3), hasher.hash_range(4..6));
+        assert_eq!(hasher.hash_range(0..7), *hasher.hashed.last().unwrap());
+        assert_eq!(hasher.hash_range(3..3), [0; 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "range out of bounds")]
+    fn hash_range_out_of_bounds() {
+        let _ = RollingHash::<2>::from_slice(&[1, 2]).hash_range(1..3);
+    }
+}
@@ -1,7 +1,16 @@
-use std::num::NonZero;
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap, HashSet},
+    num::NonZero,
+    ops::{Index, Range},
+};
 
-use crate::{BaseCount, Maybe, Prime, SupportedBaseCount, SupportedPrime, Windows, cold_path};
+use crate::{
+    BaseCount, FromRawError, Matches, Maybe, Prime, Reduce, SupportedBaseCount, SupportedPrime,
+    WindowKey, Windows, chain, cold_path, fold_lanes,
+};
 
+#[derive(Clone)]
 pub struct OneWay<const P: u64, const B: usize>
 where
     Prime<P>: SupportedPrime,
@@ -34,20 +43,65 @@ where
         }
     }
 
+    /// Creates a new instance from `slice`, where each element is [reduced](Reduce).
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN*), where *N* is `slice.len()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rolling_hash::OneWay;
+    ///
+    /// const P: u64 = (1 << 61) - 1;
+    /// let hasher = OneWay::<P, 2>::from_slice(b"hello");
+    /// assert_eq!(hasher.len(), 5);
+    /// assert_eq!(hasher.fingerprint(), hasher.hash_of(b"hello"));
+    /// ```
+    pub fn from_slice<T: Reduce<P>>(slice: &[T]) -> Self {
+        let mut hasher = Self::with_capacity(slice.len());
+        for value in slice {
+            hasher.push_reduced(value.reduce());
+        }
+        hasher
+    }
+
+    /// Creates a new instance from `slice` with specified bases, where each element is [reduced](Reduce).
+    ///
+    /// # Panics
+    ///
+    /// See [`with_base`](Self::with_base).
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN*), where *N* is `slice.len()`.
+    pub fn from_slice_with_bases<T: Reduce<P>>(slice: &[T], bases: [u64; B]) -> Self {
+        let mut hasher = Self::with_base(bases);
+        hasher.reserve(slice.len());
+        for value in slice {
+            hasher.push_reduced(value.reduce());
+        }
+        hasher
+    }
+
     /// Creates a new instance with specified bases.
     ///
+    /// Bases `0`, `1` and `P - 1` are rejected, since their powers are constant or periodic with period 2
+    /// and therefore many sequences collide.
+    ///
     /// # Panics
     ///
-    /// Panics if any of bases are `1` or `P - 1`.
+    /// Panics if any of bases are `0`, `1` or `P - 1` modulo `P`.
     pub const fn with_base(mut base: [u64; B]) -> Self {
         let mut i = 0;
         while i < B {
+            base[i] %= P;
             assert!(
-                base[i] != 1 || base[i] == P - 1,
+                Prime::<P>::is_probably_good_base(base[i]),
                 "invalid base: base should be in 2..=P - 2"
             );
 
-            base[i] %= P;
             i += 1;
         }
 
@@ -57,6 +111,66 @@ where
         }
     }
 
+    /// Reconstructs an instance from bases and prefix hashes, such as ones returned by [`base`](Self::base) and [`as_hashes`](Self::as_hashes).
+    ///
+    /// If `validate` is `true`, each prefix hash is checked to be reduced and to imply the same element for all bases.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FromRawError::InvalidBase`] if any of bases are rejected by [`with_base`](Self::with_base) without reduction,
+    /// and [`FromRawError::InconsistentHash`] if `validate` is `true` and a prefix hash is inconsistent.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*) if `validate` is `false`, and *O*(*BN*) otherwise, where *N* is `hash.len()`.
+    pub fn from_raw(
+        base: [u64; B],
+        hash: Vec<[u64; B]>,
+        validate: bool,
+    ) -> Result<Self, FromRawError> {
+        if let Some(index) = base
+            .iter()
+            .position(|&base| !Prime::<P>::is_probably_good_base(base))
+        {
+            return Err(FromRawError::InvalidBase { index });
+        }
+
+        if validate {
+            let mut prev = [0; B];
+            for (index, next) in hash.iter().enumerate() {
+                if next.iter().any(|&lane| lane >= P) {
+                    return Err(FromRawError::InconsistentHash { index });
+                }
+                let mut residues = (0..B)
+                    .map(|i| Prime::<P>::sub_mod(next[i], Prime::<P>::mul_mod(prev[i], base[i])));
+                let first = residues.next();
+                if residues.any(|residue| Some(residue) != first) {
+                    return Err(FromRawError::InconsistentHash { index });
+                }
+                prev = *next;
+            }
+        }
+
+        Ok(Self { base, hash })
+    }
+
+    /// Estimates the probability that at least one of `n_comparisons` comparisons between distinct sequences collides.
+    ///
+    /// # Model
+    ///
+    /// Each comparison is assumed to collide independently with probability `1 / P` for each base,
+    /// that is, `1 / P^B` in total, which gives `1 - (1 - 1 / P^B)^n_comparisons`.
+    /// Strictly, two distinct sequences of length *M* collide with probability at most `(M - 1) / P` for each base,
+    /// so the estimate is optimistic for very long sequences.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub fn collision_probability(n_comparisons: u64) -> f64 {
+        let p = (P as f64).powi(-(B as i32));
+        -f64::exp_m1(n_comparisons as f64 * f64::ln_1p(-p))
+    }
+
     /// Same as [`Vec::reserve`].
     #[inline]
     pub fn reserve(&mut self, additional: usize) {
@@ -84,7 +198,34 @@ where
         &self.base
     }
 
-    pub(crate) fn get_hash(&self) -> &[[u64; B]] {
+    /// Returns `true` if hashes of `self` and `other` are comparable, that is, they share bases.
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(*B*)
+    #[must_use]
+    #[inline]
+    pub fn bases_compatible(&self, other: &Self) -> bool {
+        self.base == other.base
+    }
+
+    /// Returns the prefix hashes, where the `i`-th one is the hash of the first `i + 1` elements.
+    ///
+    /// # Time Complexity
+    ///
+    /// *O*(1)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rolling_hash::DefaultHasher;
+    ///
+    /// let hasher = DefaultHasher::from_slice(b"abc");
+    /// assert_eq!(hasher.as_hashes().len(), hasher.len());
+    /// assert_eq!(hasher.as_hashes().last(), Some(&hasher.hash_of(b"abc")));
+    /// ```
+    #[inline]
+    pub fn as_hashes(&self) -> &[[u64; B]] {
         &self.hash
     }
 
@@ -94,13 +235,44 @@ where
     /// # Constraints
     ///
     /// `next < P`, otherwise overflow may or may not occur.
+    /// Since `P < 2^61`, intermediate sums are less than `2P < 2^62`.
     ///
     /// # Time complexity
     ///
     /// *O*(*B*)
     #[inline]
     fn hash_next(&self, prev: &[u64; B], next: u64) -> [u64; B] {
-        std::array::from_fn(|i| (Prime::<P>::mul_mod(prev[i], self.base[i]) + next) % P)
+        debug_assert!(next < P, "next should be reduced modulo P");
+        chain::hash_next::<Prime<P>, B>(&self.base, prev, next)
+    }
+
+    /// Recovers the element at `index` (modulo `P`) from the prefix hashes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    pub(crate) fn residue(&self, index: usize) -> u64 {
+        if index == 0 {
+            self.hash[0][0]
+        } else {
+            Prime::<P>::sub_mod(
+                self.hash[index][0],
+                Prime::<P>::mul_mod(self.hash[index - 1][0], self.base[0]),
+            )
+        }
+    }
+
+    /// Recovers the elements in `range` (modulo `P`) from the prefix hashes.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*M*), where *M* is `range.len()`.
+    pub(crate) fn residues(&self, range: Range<usize>) -> impl DoubleEndedIterator<Item = u64> {
+        range.map(|i| self.residue(i))
     }
 
     /// Hashes `slice` by using `self`.
@@ -108,22 +280,305 @@ where
     /// # Time complexity
     ///
     /// *O*(*BM*), where *M* is `slice.len()`.
-    fn hash_slice(
+    pub(crate) fn hash_slice<T: Reduce<P>>(
         &self,
-        slice: &[u64], /* intentional: iterator may skip some elements */
+        slice: &[T], /* intentional: iterator may skip some elements */
     ) -> [u64; B] {
-        slice
+        self.hash_reduced(slice.iter().map(Reduce::reduce))
+    }
+
+    /// Hashes `values` by using `self`, skipping the modulo.
+    ///
+    /// # Constraints
+    ///
+    /// Each value should be less than `P`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BM*), where *M* is the number of `values`.
+    #[inline]
+    pub(crate) fn hash_reduced(&self, values: impl IntoIterator<Item = u64>) -> [u64; B] {
+        values
+            .into_iter()
+            .fold([0; B], |prev, next| self.hash_next(&prev, next))
+    }
+
+    /// Returns the hash of `slice`, where each element is [reduced](Reduce).
+    ///
+    /// The result is comparable with hashes of any instance sharing bases with `self`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BM*), where *M* is `slice.len()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rolling_hash::DefaultHasher;
+    ///
+    /// let hasher = DefaultHasher::from_slice(b"abcabd");
+    /// let target = hasher.hash_of(b"ab");
+    /// let found: Vec<_> = hasher
+    ///     .window_hashes(2)
+    ///     .filter_map(|(i, hash)| (hash == target).then_some(i))
+    ///     .collect();
+    /// assert_eq!(found, [0, 3]);
+    /// ```
+    #[inline]
+    pub fn hash_of<T: Reduce<P>>(&self, slice: &[T]) -> [u64; B] {
+        self.hash_slice(slice)
+    }
+
+    /// Returns the hash of the whole sequence, or `[0; B]` if `self` is empty.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*)
+    #[inline]
+    pub fn fingerprint(&self) -> [u64; B] {
+        self.hash.last().copied().unwrap_or([0; B])
+    }
+
+    /// Returns the hash of the whole sequence, folded into a single `u64`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*)
+    #[inline]
+    pub fn fingerprint_u64(&self) -> u64 {
+        fold_lanes(&self.fingerprint())
+    }
+
+    /// Returns the hash of the sub slice in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B* log *M*), where *M* is `range.len()`.
+    pub fn hash_range(&self, range: Range<usize>) -> [u64; B] {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "range out of bounds"
+        );
+
+        let base_pow_len = self.base_pow(range.len());
+        self.hash_range_with(range, &base_pow_len)
+    }
+
+    /// Returns hashes of the sub slices in `ranges`.
+    ///
+    /// `base^len` is computed only once for each distinct length in `ranges`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of `ranges` is out of bounds.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*(*K* + *L* log *M*)) on average, where *K* is the number of `ranges`,
+    /// *L* is the number of their distinct lengths and *M* is the maximum length.
+    pub fn hash_ranges(&self, ranges: impl IntoIterator<Item = Range<usize>>) -> Vec<[u64; B]> {
+        let mut base_pow_cache = HashMap::new();
+        ranges
             .into_iter()
-            .fold([0; B], |prev, next| self.hash_next(&prev, next % P))
+            .map(|range| {
+                assert!(
+                    range.start <= range.end && range.end <= self.len(),
+                    "range out of bounds"
+                );
+                let base_pow_len = base_pow_cache
+                    .entry(range.len())
+                    .or_insert_with(|| self.base_pow(range.len()));
+                self.hash_range_with(range, base_pow_len)
+            })
+            .collect()
+    }
+
+    /// Returns the length of the longest common prefix of suffixes starting at `i` and `j`,
+    /// where `base_pow(len)` returns `base^len`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` or `j` is greater than `len`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log *N*) calls of `base_pow`, where *N* is `self.len()`.
+    fn lcp_with(&self, i: usize, j: usize, base_pow: impl Fn(usize) -> [u64; B]) -> usize {
+        let (mut ok, mut ng) = (0, (self.len() - i).min(self.len() - j) + 1);
+        while ng - ok > 1 {
+            let mid = ok + (ng - ok) / 2;
+            let base_pow_mid = base_pow(mid);
+            if self.hash_range_with(i..i + mid, &base_pow_mid)
+                == self.hash_range_with(j..j + mid, &base_pow_mid)
+            {
+                ok = mid;
+            } else {
+                ng = mid;
+            }
+        }
+        ok
+    }
+
+    /// Compares suffixes starting at `i` and `j` lexicographically, by their elements modulo `P`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` or `j` is greater than `len`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B* (log *N*)^2), where *N* is `self.len()`.
+    pub fn compare_suffixes(&self, i: usize, j: usize) -> Maybe<Ordering> {
+        Maybe::new(self.compare_suffixes_with(i, j, |len| self.base_pow(len)))
+    }
+
+    /// Same as [`compare_suffixes`](Self::compare_suffixes), where `base_pow(len)` returns `base^len`.
+    fn compare_suffixes_with(
+        &self,
+        i: usize,
+        j: usize,
+        base_pow: impl Fn(usize) -> [u64; B],
+    ) -> Ordering {
+        if i == j {
+            return Ordering::Equal;
+        }
+
+        let lcp = self.lcp_with(i, j, base_pow);
+        if i + lcp == self.len() || j + lcp == self.len() {
+            // one is a prefix of the other
+            j.cmp(&i)
+        } else {
+            self.residue(i + lcp).cmp(&self.residue(j + lcp))
+        }
+    }
+
+    /// Returns the suffix array, that is, start indexes of suffixes in lexicographic order.
+    ///
+    /// Elements are compared modulo `P`.
+    /// Since all suffixes have distinct lengths, there are no ties.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN* (log *N*)^2), where *N* is `self.len()`.
+    pub fn suffix_array(&self) -> Maybe<Vec<usize>> {
+        let base_pows = self.base_pows(self.len());
+        let mut suffix_array: Vec<_> = (0..self.len()).collect();
+        suffix_array
+            .sort_unstable_by(|&i, &j| self.compare_suffixes_with(i, j, |len| base_pows[len]));
+        Maybe::new(suffix_array)
+    }
+
+    /// Returns the start index of the lexicographically smallest rotation, or `0` if `self` is empty.
+    ///
+    /// Rotations are compared as sub slices of the doubled sequence, by the longest common prefix and then the next element (modulo `P`).
+    /// If several rotations are the smallest, the smallest index is returned.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN* log *N*), where *N* is `self.len()`.
+    pub fn least_rotation(&self) -> Maybe<usize> {
+        let n = self.len();
+        let mut doubled = self.clone();
+        doubled.extend_from_within(0..n);
+        let base_pows = doubled.base_pows(doubled.len());
+
+        Maybe::new(
+            (0..n)
+                .min_by(|&i, &j| {
+                    let lcp = doubled.lcp_with(i, j, |len| base_pows[len]);
+                    if lcp >= n {
+                        Ordering::Equal
+                    } else {
+                        doubled.residue(i + lcp).cmp(&doubled.residue(j + lcp))
+                    }
+                })
+                .unwrap_or(0),
+        )
+    }
+
+    /// Returns the Z-array, where `z[i]` is the length of the longest common prefix of `self` and its suffix starting at `i`.
+    ///
+    /// In particular, `z[0]` is `self.len()`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN* log *N*), where *N* is `self.len()`.
+    pub fn z_array(&self) -> Maybe<Vec<usize>> {
+        let base_pows = self.base_pows(self.len());
+        Maybe::new(
+            (0..self.len())
+                .map(|i| self.lcp_with(0, i, |len| base_pows[len]))
+                .collect(),
+        )
+    }
+
+    /// Returns `base^len` for each base and each `len` in `0..=max_len`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B* *max_len*)
+    pub(crate) fn base_pows(&self, max_len: usize) -> Vec<[u64; B]> {
+        let mut base_pows = Vec::with_capacity(max_len + 1);
+        base_pows.push([1; B]);
+        for len in 0..max_len {
+            let prev: &[u64; B] = &base_pows[len];
+            base_pows.push(std::array::from_fn(|i| {
+                Prime::<P>::mul_mod(prev[i], self.base[i])
+            }));
+        }
+        base_pows
+    }
+
+    /// Returns `base^len` for each base.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` does not fit in `u64`, which is impossible on targets up to 64-bit.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B* log *len*)
+    #[inline]
+    pub(crate) fn base_pow(&self, len: usize) -> [u64; B] {
+        chain::base_pow::<Prime<P>, B>(&self.base, len)
+    }
+
+    /// Returns the hash of the sub slice in `range`, where `base_pow_len` is `base^range.len()`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*)
+    #[inline]
+    pub(crate) fn hash_range_with(&self, range: Range<usize>, base_pow_len: &[u64; B]) -> [u64; B] {
+        chain::hash_range_with::<Prime<P>, B>(&self.hash, range, base_pow_len)
+    }
+
+    /// Appends an element to the back of `self`, where the element is [reduced](Reduce).
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*)
+    #[inline]
+    pub fn push<T: Reduce<P>>(&mut self, value: T) {
+        self.push_reduced(value.reduce());
     }
 
-    /// Appends an element to the back of `self`.
+    /// Appends an element to the back of `self`, skipping the modulo.
+    ///
+    /// # Constraints
+    ///
+    /// `value < P`, which is debug-asserted.
     ///
     /// # Time complexity
     ///
     /// *O*(*B*)
     #[inline]
-    pub fn push(&mut self, value: u64) {
+    pub(crate) fn push_reduced(&mut self, value: u64) {
+        debug_assert!(value < P, "value should be reduced modulo P");
         self.hash.push(if let Some(prev) = self.hash.last() {
             self.hash_next(prev, value)
         } else {
@@ -132,77 +587,1944 @@ where
         });
     }
 
-    /// Moves all the elements of `other` into `self`, leaving `other` empty.
+    /// Divides `self` into two at an index.
+    ///
+    /// The first will contain all indices from `[0, mid)` and the second will contain all indices from `[mid, len)`.
+    /// Both of them share bases with `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid > len`.
     ///
     /// # Time complexity
     ///
-    /// *O*(*BM*), where *M* is `other.len()`
-    pub fn append(&mut self, other: &mut Vec<u64>) {
-        self.reserve(other.len());
-        for value in other.drain(..) {
-            self.push(value);
+    /// *O*(*BN*), where *N* is `self.len()`.
+    pub fn split_at(&self, mid: usize) -> (Self, Self) {
+        assert!(mid <= self.len(), "mid > len");
+
+        let left = Self {
+            base: self.base,
+            hash: self.hash[..mid].to_vec(),
+        };
+        let mut right = Self {
+            base: self.base,
+            hash: Vec::with_capacity(self.len() - mid),
+        };
+        for value in self.residues(mid..self.len()) {
+            right.push_reduced(value);
         }
+
+        (left, right)
     }
 
-    /// # Panics
-    ///
-    /// Panics if `size` is `0`.
+    /// Creates a new instance for the reversed sequence, sharing bases with `self`.
     ///
     /// # Time complexity
     ///
-    /// *O*(*B*)
-    fn windows(&self, size: usize) -> Windows<'_, P, B> {
-        let size = NonZero::new(size).expect("slice must not be empty");
-        Windows::new(self, size)
+    /// *O*(*BN*), where *N* is `self.len()`.
+    pub fn reversed(&self) -> Self {
+        let mut reversed = Self {
+            base: self.base,
+            hash: Vec::with_capacity(self.len()),
+        };
+        for value in self.residues(0..self.len()).rev() {
+            reversed.push_reduced(value);
+        }
+
+        reversed
     }
 
-    /// Searches for an sub slice in `self`, returning its index.
+    /// Retains only the elements (modulo `P`) specified by the predicate.
+    ///
+    /// Since every prefix hash depends on all the preceding elements, the whole chain is rebuilt.
     ///
     /// # Time complexity
     ///
     /// *O*(*BN*), where *N* is `self.len()`.
-    pub fn position(&self, slice: &[u64]) -> Option<Maybe<usize>> {
-        let target = self.hash_slice(slice);
-        self.windows(slice.len())
-            .position(|sub_slice| sub_slice == target)
-            .map(|i| Maybe(i))
+    pub fn retain(&mut self, mut f: impl FnMut(u64) -> bool) {
+        let residues: Vec<_> = self.residues(0..self.len()).collect();
+        self.hash.clear();
+        for value in residues {
+            if f(value) {
+                self.push_reduced(value);
+            }
+        }
     }
 
-    /// Searches for sub slice in `self` from the right, returning its index.
+    /// Removes consecutive repeated elements (modulo `P`), like [`Vec::dedup`].
+    ///
+    /// Since every prefix hash depends on all the preceding elements, the whole chain is rebuilt.
     ///
     /// # Time complexity
     ///
     /// *O*(*BN*), where *N* is `self.len()`.
-    pub fn rposition(&self, slice: &[u64]) -> Option<Maybe<usize>> {
-        let target = self.hash_slice(slice);
-        self.windows(slice.len())
-            .rposition(|sub_slice| sub_slice == target)
-            .map(|i| Maybe(i))
+    pub fn dedup(&mut self) {
+        let residues: Vec<_> = self.residues(0..self.len()).collect();
+        self.hash.clear();
+        for (i, &value) in residues.iter().enumerate() {
+            if i == 0 || residues[i - 1] != value {
+                self.push_reduced(value);
+            }
+        }
     }
 
-    /// Searches for sub slice in `self`, returning all indexes.
+    /// Rotates the elements in place such that the first `mid` elements move to the end, like [`slice::rotate_left`].
+    ///
+    /// Since every prefix hash depends on all the preceding elements, the whole chain is rebuilt.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid > len`.
     ///
     /// # Time complexity
     ///
     /// *O*(*BN*), where *N* is `self.len()`.
-    pub fn positions(&self, slice: &[u64]) -> impl Iterator<Item = Maybe<usize>> {
-        let target = self.hash_slice(slice);
-        self.windows(slice.len())
-            .enumerate()
-            .filter_map(move |(i, sub_slice)| (sub_slice == target).then_some(Maybe(i)))
+    pub fn rotate_left(&mut self, mid: usize) {
+        assert!(mid <= self.len(), "mid > len");
+
+        let mut residues: Vec<_> = self.residues(0..self.len()).collect();
+        residues.rotate_left(mid);
+        self.hash.clear();
+        for value in residues {
+            self.push_reduced(value);
+        }
     }
 
-    /// Counts sub slices in `self`.
+    /// Rotates the elements in place such that the last `k` elements move to the front, like [`slice::rotate_right`].
+    ///
+    /// Since every prefix hash depends on all the preceding elements, the whole chain is rebuilt.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k > len`.
     ///
     /// # Time complexity
     ///
     /// *O*(*BN*), where *N* is `self.len()`.
-    pub fn count(&self, slice: &[u64]) -> Maybe<usize> {
-        let target = self.hash_slice(slice);
-        Maybe(
-            self.windows(slice.len())
-                .filter(|sub_slice| sub_slice == &target)
-                .count(),
-        )
+    pub fn rotate_right(&mut self, k: usize) {
+        assert!(k <= self.len(), "k > len");
+
+        self.rotate_left(self.len() - k);
+    }
+
+    /// Inserts an element at position `index`, shifting all elements after it to the right, where the element is [reduced](Reduce).
+    ///
+    /// The prefix hashes before `index` stay valid, and the rest of the chain is rebuilt.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*(*N* - *index*)), where *N* is `self.len()`.
+    pub fn insert<T: Reduce<P>>(&mut self, index: usize, value: T) {
+        assert!(index <= self.len(), "insertion index should be <= len");
+
+        let suffix: Vec<_> = self.residues(index..self.len()).collect();
+        self.hash.truncate(index);
+        self.push(value);
+        for value in suffix {
+            self.push_reduced(value);
+        }
+    }
+
+    /// Removes and returns the element (modulo `P`) at position `index`, shifting all elements after it to the left.
+    ///
+    /// The prefix hashes before `index` stay valid, and the rest of the chain is rebuilt.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*(*N* - *index*)), where *N* is `self.len()`.
+    pub fn remove(&mut self, index: usize) -> u64 {
+        assert!(index < self.len(), "removal index should be < len");
+
+        let removed = self.residue(index);
+        let suffix: Vec<_> = self.residues(index + 1..self.len()).collect();
+        self.hash.truncate(index);
+        for value in suffix {
+            self.push_reduced(value);
+        }
+
+        removed
+    }
+
+    /// Moves all the elements of `other` into `self`, leaving `other` empty, where each element is [reduced](Reduce).
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BM*), where *M* is `other.len()`
+    pub fn append<T: Reduce<P>>(&mut self, other: &mut Vec<T>) {
+        self.reserve(other.len());
+        for value in other.drain(..) {
+            self.push(value);
+        }
+    }
+
+    /// Appends all the elements of `slice` into `self`, leaving `slice` intact, where each element is [reduced](Reduce).
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BM*), where *M* is `slice.len()`
+    pub fn append_slice<T: Reduce<P>>(&mut self, slice: &[T]) {
+        self.reserve(slice.len());
+        for value in slice {
+            self.push_reduced(value.reduce());
+        }
+    }
+
+    /// Appends all the elements of `iter` into `self`, where each element is [reduced](Reduce).
+    ///
+    /// Since the length of `iter` is known, the capacity is reserved only once.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BM*), where *M* is the length of `iter`.
+    pub fn extend_exact<T, I>(&mut self, iter: I)
+    where
+        T: Reduce<P>,
+        I: IntoIterator<Item = T, IntoIter: ExactSizeIterator>,
+    {
+        let iter = iter.into_iter();
+        self.reserve(iter.len());
+        for value in iter {
+            self.push_reduced(value.reduce());
+        }
+    }
+
+    /// Copies elements (modulo `P`) in `range` to the end of `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BM*), where *M* is `range.len()`
+    pub fn extend_from_within(&mut self, range: Range<usize>) {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "range out of bounds"
+        );
+
+        let residues: Vec<_> = self.residues(range).collect();
+        self.reserve(residues.len());
+        for value in residues {
+            self.push_reduced(value);
+        }
+    }
+
+    /// Returns an iterator over hashes of all contiguous windows of length `size`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is `0`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*)
+    pub fn windows(&self, size: usize) -> Windows<'_, P, B> {
+        let size = NonZero::new(size).expect("slice must not be empty");
+        Windows::new(&self.hash, self.base, size)
+    }
+
+    /// Returns an iterator over hashes of all contiguous windows of length `size`, from right to left.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is `0`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rolling_hash::DefaultHasher;
+    ///
+    /// let hasher = DefaultHasher::from_slice(b"abcd");
+    /// assert_eq!(hasher.windows(2).last(), hasher.windows_rev(2).next());
+    /// assert_eq!(hasher.windows_rev(2).next(), Some(hasher.hash_of(b"cd")));
+    /// ```
+    #[inline]
+    pub fn windows_rev(
+        &self,
+        size: usize,
+    ) -> impl DoubleEndedIterator<Item = [u64; B]> + ExactSizeIterator {
+        self.windows(size).rev()
+    }
+
+    /// Returns an iterator over `f` applied to hashes of all contiguous windows of length `size`.
+    ///
+    /// Skipping with `nth` stays *O*(*B*), since [`Map`](std::iter::Map) forwards it to [`Windows`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is `0`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rolling_hash::DefaultHasher;
+    ///
+    /// let hasher = DefaultHasher::from_slice(b"abcd");
+    /// let first_lanes: Vec<u64> = hasher.windows_map(2, |hash| hash[0]).collect();
+    /// assert_eq!(first_lanes.len(), 3);
+    /// assert_eq!(first_lanes[1], hasher.hash_of(b"bc")[0]);
+    /// ```
+    #[inline]
+    pub fn windows_map<R>(
+        &self,
+        size: usize,
+        f: impl FnMut([u64; B]) -> R,
+    ) -> impl DoubleEndedIterator<Item = R> + ExactSizeIterator {
+        self.windows(size).map(f)
+    }
+
+    /// Returns an iterator over pairs of the start index and the hash of all contiguous windows of length `size`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is `0`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rolling_hash::DefaultHasher;
+    ///
+    /// let hasher = DefaultHasher::from_slice(b"abca");
+    /// let pairs: Vec<_> = hasher.window_hashes(2).collect();
+    /// assert_eq!(
+    ///     pairs,
+    ///     [(0, hasher.hash_of(b"ab")), (1, hasher.hash_of(b"bc")), (2, hasher.hash_of(b"ca"))]
+    /// );
+    /// ```
+    #[inline]
+    pub fn window_hashes(
+        &self,
+        size: usize,
+    ) -> impl DoubleEndedIterator<Item = (usize, [u64; B])> + ExactSizeIterator {
+        self.windows(size).enumerate()
+    }
+
+    /// Returns an iterator over pairs of the start index and the hash of all k-mers, that is, windows of length `k`.
+    ///
+    /// This is the same as [`window_hashes`](Self::window_hashes).
+    /// See also [`BidirectionalRollingHash::canonical_kmers`](crate::BidirectionalRollingHash::canonical_kmers),
+    /// which identifies a k-mer with its reverse.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is `0`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rolling_hash::DefaultHasher;
+    ///
+    /// let hasher = DefaultHasher::from_slice(b"GATTACAGATT");
+    /// let gat = hasher.hash_of(b"GAT");
+    /// let found: Vec<_> = hasher
+    ///     .kmers(3)
+    ///     .filter_map(|(i, hash)| (hash == gat).then_some(i))
+    ///     .collect();
+    /// assert_eq!(found, [0, 7]);
+    /// assert_eq!(hasher.kmers(3).len(), 9);
+    /// ```
+    #[inline]
+    pub fn kmers(
+        &self,
+        k: usize,
+    ) -> impl DoubleEndedIterator<Item = (usize, [u64; B])> + ExactSizeIterator {
+        self.window_hashes(k)
+    }
+
+    /// Returns index ranges of distinct windows of length `len`, each of which is the first occurrence.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is `0`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN*) on average, where *N* is `self.len()`.
+    pub fn distinct_windows(&self, len: usize) -> Maybe<Vec<Range<usize>>> {
+        let mut seen = HashSet::new();
+        Maybe::new(
+            self.windows(len)
+                .enumerate()
+                .filter_map(|(i, hash)| seen.insert(WindowKey(hash)).then_some(i..i + len))
+                .collect(),
+        )
+    }
+
+    /// Returns the minimum hash of windows of length `window` for each base, that is, a MinHash sketch.
+    ///
+    /// If there is no such window, `u64::MAX` is returned for all bases.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` is `0`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN*), where *N* is `self.len()`.
+    pub fn min_sketch(&self, window: usize) -> [u64; B] {
+        self.windows(window).fold([u64::MAX; B], |min, hash| {
+            std::array::from_fn(|i| min[i].min(hash[i]))
+        })
+    }
+
+    /// Estimates the Jaccard similarity between sets of windows of length `window` in `a` and `b`.
+    ///
+    /// The result is the fraction of bases whose [MinHash sketches](Self::min_sketch) agree,
+    /// so its resolution is `1 / B`.
+    /// If `a` or `b` is shorter than `window`, `0.0` is returned, since it has no windows to share.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `a` and `b` do not share bases.
+    /// - Panics if `window` is `0`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*(*N* + *M*)), where *N* is `a.len()` and *M* is `b.len()`.
+    pub fn jaccard_estimate(a: &Self, b: &Self, window: usize) -> f64 {
+        assert!(a.bases_compatible(b), "hashers should share bases");
+
+        let (sketch_a, sketch_b) = (a.min_sketch(window), b.min_sketch(window));
+        if a.len() < window || b.len() < window {
+            // both sketches may be `[u64::MAX; B]`, which agree on every base
+            return 0.0;
+        }
+        let agreed = sketch_a
+            .iter()
+            .zip(&sketch_b)
+            .filter(|(a, b)| a == b)
+            .count();
+        agreed as f64 / B as f64
+    }
+
+    /// Returns the first index where the sequences of `a` and `b` differ, or `None` if they are equal.
+    ///
+    /// If one is a proper prefix of the other, the shorter length is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` and `b` do not share bases.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B* log *M*), where *M* is `a.len().min(b.len())`.
+    #[must_use]
+    pub fn first_difference(a: &Self, b: &Self) -> Option<Maybe<usize>> {
+        assert!(a.bases_compatible(b), "hashers should share bases");
+
+        // prefix hashes agree for all lengths up to the answer, and never after it
+        let (mut ok, mut ng) = (0, a.len().min(b.len()) + 1);
+        while ng - ok > 1 {
+            let mid = ok + (ng - ok) / 2;
+            if a.hash[mid - 1] == b.hash[mid - 1] {
+                ok = mid;
+            } else {
+                ng = mid;
+            }
+        }
+        (ok < a.len().max(b.len())).then_some(Maybe::new(ok))
+    }
+
+    /// Returns the length of the longest common suffix of the sequences of `a` and `b`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` and `b` do not share bases.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B* (log *M*)^2), where *M* is `a.len().min(b.len())`.
+    pub fn common_suffix_len(a: &Self, b: &Self) -> Maybe<usize> {
+        assert!(a.bases_compatible(b), "hashers should share bases");
+
+        // suffix hashes agree for all lengths up to the answer, and never after it
+        let (mut ok, mut ng) = (0, a.len().min(b.len()) + 1);
+        while ng - ok > 1 {
+            let mid = ok + (ng - ok) / 2;
+            let base_pow_mid = a.base_pow(mid);
+            if a.hash_range_with(a.len() - mid..a.len(), &base_pow_mid)
+                == b.hash_range_with(b.len() - mid..b.len(), &base_pow_mid)
+            {
+                ok = mid;
+            } else {
+                ng = mid;
+            }
+        }
+        Maybe::new(ok)
+    }
+
+    /// Searches for an sub slice in `self`, returning its index.
+    ///
+    /// An empty `slice` matches at every index in `0..=self.len()`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN*), where *N* is `self.len()`.
+    #[must_use]
+    pub fn position<T: Reduce<P>>(&self, slice: &[T]) -> Option<Maybe<usize>> {
+        if slice.is_empty() {
+            return Some(Maybe::certain(0));
+        }
+        if slice.len() > self.len() {
+            return None;
+        }
+        let target = self.hash_slice(slice);
+        self.windows(slice.len())
+            .position(|sub_slice| sub_slice == target)
+            .map(Maybe::new)
+    }
+
+    /// Searches for an sub slice in `self` with at most `k` mismatched elements, returning its index.
+    ///
+    /// Each window is checked by the Kangaroo method, that is, the longest common prefix with `needle` is skipped by hashing,
+    /// and then a mismatch is skipped, until more than `k` mismatches are found.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BM* + *BKN* log *M*), where *N* is `self.len()` and *M* is `needle.len()`.
+    #[must_use]
+    pub fn position_with_mismatches<T: Reduce<P>>(
+        &self,
+        needle: &[T],
+        k: usize,
+    ) -> Option<Maybe<usize>> {
+        if needle.is_empty() {
+            return Some(Maybe::certain(0));
+        }
+        if needle.len() > self.len() {
+            return None;
+        }
+        let needle_hasher = Self::from_slice_with_bases(needle, self.base);
+        let base_pows = self.base_pows(needle.len());
+
+        (0..=self.len() - needle.len())
+            .find(|&i| {
+                let (mut j, mut mismatches) = (0, 0);
+                loop {
+                    // the longest common prefix of `self[i + j..]` and `needle[j..]`
+                    let (mut ok, mut ng) = (0, needle.len() - j + 1);
+                    while ng - ok > 1 {
+                        let mid = ok + (ng - ok) / 2;
+                        if self.hash_range_with(i + j..i + j + mid, &base_pows[mid])
+                            == needle_hasher.hash_range_with(j..j + mid, &base_pows[mid])
+                        {
+                            ok = mid;
+                        } else {
+                            ng = mid;
+                        }
+                    }
+                    j += ok;
+                    if j == needle.len() {
+                        return true;
+                    }
+                    mismatches += 1;
+                    if mismatches > k {
+                        return false;
+                    }
+                    j += 1;
+                }
+            })
+            .map(Maybe::new)
+    }
+
+    /// Searches for an sub slice in `self`, returning its index.
+    /// Candidates are compared with `source` directly by [`PartialEq`], so the result is always correct.
+    ///
+    /// # Constraints
+    ///
+    /// `self` should be built from `source`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN* + *KM*), where *N* is `self.len()`, *M* is `needle.len()`
+    /// and *K* is the number of candidates.
+    #[must_use]
+    pub fn position_verified<T: Reduce<P> + PartialEq>(
+        &self,
+        source: &[T],
+        needle: &[T],
+    ) -> Option<usize> {
+        self.positions(needle)
+            .map(|i| *i)
+            .find(|&i| source[i..i + needle.len()] == *needle)
+    }
+
+    /// Returns `true` if `self` contains `slice` as a sub slice.
+    ///
+    /// An empty `slice` matches at every index in `0..=self.len()`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN*), where *N* is `self.len()`.
+    pub fn contains<T: Reduce<P>>(&self, slice: &[T]) -> Maybe<bool> {
+        if slice.is_empty() {
+            return Maybe::certain(true);
+        }
+        let target = self.hash_slice(slice);
+        Maybe::new(
+            self.windows(slice.len())
+                .any(|sub_slice| sub_slice == target),
+        )
+    }
+
+    /// Returns `true` if `self` contains a run of `count` elements equal to `value`, where `value` is [reduced](Reduce).
+    ///
+    /// Windows are compared with the hash of the run, which is computed without materializing it.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*(*N* + *count*)), where *N* is `self.len()`.
+    pub fn contains_run_of<T: Reduce<P>>(&self, value: T, count: usize) -> Maybe<bool> {
+        if count == 0 {
+            return Maybe::certain(true);
+        }
+        if count > self.len() {
+            return Maybe::certain(false);
+        }
+        let target = self.hash_reduced(std::iter::repeat_n(value.reduce(), count));
+        Maybe::new(self.windows(count).any(|sub_slice| sub_slice == target))
+    }
+
+    /// Returns `true` if the whole sequence of `self` equals `slice`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BM*), where *M* is `slice.len()`.
+    pub fn equals_slice<T: Reduce<P>>(&self, slice: &[T]) -> Maybe<bool> {
+        Maybe::new(slice.len() == self.len() && self.fingerprint() == self.hash_slice(slice))
+    }
+
+    /// Returns `true` if `prefix` is a prefix of `self`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BM*), where *M* is `prefix.len()`.
+    pub fn starts_with<T: Reduce<P>>(&self, prefix: &[T]) -> Maybe<bool> {
+        Maybe::new(
+            prefix.len() <= self.len()
+                && self.hash_range(0..prefix.len()) == self.hash_slice(prefix),
+        )
+    }
+
+    /// Returns the length of the longest common prefix of `self` and `slice`.
+    ///
+    /// Prefix hashes of `slice` are compared with those of `self` from the shortest one, stopping at the first mismatch.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BL*), where *L* is the result.
+    pub fn common_prefix_len<T: Reduce<P>>(&self, slice: &[T]) -> Maybe<usize> {
+        let mut prev = [0; B];
+        Maybe::new(
+            slice
+                .iter()
+                .zip(&self.hash)
+                .take_while(|(value, hash)| {
+                    prev = self.hash_next(&prev, value.reduce());
+                    prev == **hash
+                })
+                .count(),
+        )
+    }
+
+    /// Returns `true` if `suffix` is a suffix of `self`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BM*), where *M* is `suffix.len()`.
+    pub fn ends_with<T: Reduce<P>>(&self, suffix: &[T]) -> Maybe<bool> {
+        Maybe::new(
+            suffix.len() <= self.len()
+                && self.hash_range(self.len() - suffix.len()..self.len())
+                    == self.hash_slice(suffix),
+        )
+    }
+
+    /// Searches for sub slice in `self` in parallel, returning all indexes in ascending order.
+    ///
+    /// An empty `slice` matches at every index in `0..=self.len()`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN*) work, where *N* is `self.len()`.
+    #[cfg(feature = "rayon")]
+    #[must_use]
+    pub fn par_positions<T: Reduce<P>>(&self, slice: &[T]) -> Vec<Maybe<usize>> {
+        use rayon::prelude::*;
+
+        if slice.len() > self.len() {
+            return Vec::new();
+        }
+        let (target, len) = (self.hash_slice(slice), slice.len());
+        let base_pow_len = self.base_pow(len);
+        (0..=self.len() - len)
+            .into_par_iter()
+            .filter(|&i| self.hash_range_with(i..i + len, &base_pow_len) == target)
+            .map(Maybe::new)
+            .collect()
+    }
+
+    /// Searches for each of `needles` in `self`, returning their indexes in the same order.
+    ///
+    /// Needles of the same length share a single scan of `self`.
+    /// An empty needle is found at `0`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*(*LN* + *M*)) on average, where *N* is `self.len()`, *L* is the number of distinct lengths of `needles`,
+    /// and *M* is the total length of `needles`.
+    #[must_use]
+    pub fn bulk_query<T: Reduce<P>>(&self, needles: &[&[T]]) -> Vec<Option<Maybe<usize>>> {
+        let mut groups: BTreeMap<usize, HashMap<WindowKey<B>, Vec<usize>>> = BTreeMap::new();
+        for (id, needle) in needles.iter().enumerate() {
+            groups
+                .entry(needle.len())
+                .or_default()
+                .entry(WindowKey(self.hash_slice(needle)))
+                .or_default()
+                .push(id);
+        }
+
+        let mut result: Vec<_> = std::iter::repeat_with(|| None)
+            .take(needles.len())
+            .collect();
+        if let Some(targets) = groups.remove(&0) {
+            for id in targets.into_values().flatten() {
+                result[id] = Some(Maybe::certain(0));
+            }
+        }
+        for (len, mut targets) in groups {
+            for (i, hash) in self.window_hashes(len) {
+                if let Some(ids) = targets.remove(&WindowKey(hash)) {
+                    for id in ids {
+                        result[id] = Some(Maybe::new(i));
+                    }
+                    if targets.is_empty() {
+                        break;
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Counts each of `needles` in `self`, returning their counts in the same order.
+    ///
+    /// Needles of the same length share a single scan of `self`.
+    /// An empty needle is counted `self.len() + 1` times.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*(*LN* + *M*)) on average, where *N* is `self.len()`, *L* is the number of distinct lengths of `needles`,
+    /// and *M* is the total length of `needles`.
+    #[must_use]
+    pub fn count_many<T: Reduce<P>>(&self, needles: &[&[T]]) -> Vec<Maybe<usize>> {
+        let keys: Vec<_> = needles
+            .iter()
+            .map(|needle| WindowKey(self.hash_slice(needle)))
+            .collect();
+
+        let mut groups: BTreeMap<usize, HashMap<WindowKey<B>, usize>> = BTreeMap::new();
+        for (needle, &key) in needles.iter().zip(&keys) {
+            groups.entry(needle.len()).or_default().insert(key, 0);
+        }
+        for (&len, counts) in groups.iter_mut() {
+            if len == 0 {
+                counts
+                    .values_mut()
+                    .for_each(|count| *count = self.len() + 1);
+                continue;
+            }
+            for hash in self.windows(len) {
+                if let Some(count) = counts.get_mut(&WindowKey(hash)) {
+                    *count += 1;
+                }
+            }
+        }
+
+        needles
+            .iter()
+            .zip(keys)
+            .map(|(needle, key)| {
+                let count = groups[&needle.len()][&key];
+                if needle.is_empty() {
+                    Maybe::certain(count)
+                } else {
+                    Maybe::new(count)
+                }
+            })
+            .collect()
+    }
+
+    /// Searches for sub slice in `self` from the right, returning its index.
+    ///
+    /// An empty `slice` matches at every index in `0..=self.len()`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN*), where *N* is `self.len()`.
+    #[must_use]
+    pub fn rposition<T: Reduce<P>>(&self, slice: &[T]) -> Option<Maybe<usize>> {
+        if slice.is_empty() {
+            return Some(Maybe::certain(self.len()));
+        }
+        if slice.len() > self.len() {
+            return None;
+        }
+        let target = self.hash_slice(slice);
+        self.windows(slice.len())
+            .rposition(|sub_slice| sub_slice == target)
+            .map(Maybe::new)
+    }
+
+    /// Searches for sub slice in `self` starting at `start` or later, returning its index.
+    ///
+    /// If `start` is past the end, `None` is returned.
+    /// An empty `slice` is found at `start` if `start <= self.len()`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*(*N* - *start*)), where *N* is `self.len()`.
+    #[must_use]
+    pub fn position_after<T: Reduce<P>>(&self, slice: &[T], start: usize) -> Option<Maybe<usize>> {
+        if slice.is_empty() {
+            return (start <= self.len()).then_some(Maybe::certain(start));
+        }
+        if start >= self.len() {
+            return None;
+        }
+        let target = self.hash_slice(slice);
+        self.windows(slice.len())
+            .skip(start)
+            .position(|sub_slice| sub_slice == target)
+            .map(|i| Maybe::new(start + i))
+    }
+
+    /// Searches for sub slice in `self[..end]` from the right, returning its index.
+    ///
+    /// An empty `slice` is found at `end`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `end > len`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN*), where *N* is `end`.
+    #[must_use]
+    pub fn rposition_before<T: Reduce<P>>(&self, slice: &[T], end: usize) -> Option<Maybe<usize>> {
+        assert!(end <= self.len(), "end > len");
+
+        if slice.is_empty() {
+            return Some(Maybe::certain(end));
+        }
+        if slice.len() > end {
+            return None;
+        }
+        let target = self.hash_slice(slice);
+        self.windows(slice.len())
+            .take(end - slice.len() + 1)
+            .rposition(|sub_slice| sub_slice == target)
+            .map(Maybe::new)
+    }
+
+    /// Searches for sub slice in `self`, returning all indexes.
+    ///
+    /// An empty `slice` matches at every index in `0..=self.len()`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN*), where *N* is `self.len()`.
+    pub fn positions<T: Reduce<P>>(&self, slice: &[T]) -> impl Iterator<Item = Maybe<usize>> {
+        self.matches(slice)
+    }
+
+    /// Searches for sub slice in `self`, returning all indexes from either end.
+    ///
+    /// An empty `slice` matches at every index in `0..=self.len()`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN*), where *N* is `self.len()`.
+    pub fn matches<T: Reduce<P>>(&self, slice: &[T]) -> Matches<'_, P, B> {
+        if slice.is_empty() {
+            return Matches::everywhere(self.len() + 1);
+        }
+        if slice.len() > self.len() {
+            // no windows, so the target is never compared
+            return Matches::new(self.windows(slice.len()), [0; B]);
+        }
+        Matches::new(self.windows(slice.len()), self.hash_slice(slice))
+    }
+
+    /// Searches for each of `needles` in `self`, returning pairs of the needle index and a position in ascending order of positions.
+    ///
+    /// Matches at the same position are ordered by the needle index.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BKN* + *KR*), where *N* is `self.len()`, *K* is the number of `needles` and *R* is the number of matches.
+    pub fn positions_tagged<T: Reduce<P>>(
+        &self,
+        needles: &[&[T]],
+    ) -> impl Iterator<Item = (usize, Maybe<usize>)> {
+        let mut matches: Vec<_> = needles
+            .iter()
+            .map(|needle| self.matches(needle).peekable())
+            .collect();
+        std::iter::from_fn(move || {
+            let (id, _) = matches
+                .iter_mut()
+                .enumerate()
+                .filter_map(|(id, matches)| Some((id, **matches.peek()?)))
+                .min_by_key(|&(id, position)| (position, id))?;
+            Some((id, matches[id].next()?))
+        })
+    }
+
+    /// Returns the elements (modulo `P`) of `self`, where non-overlapping occurrences of `needle` are replaced with `replacement`.
+    ///
+    /// Occurrences are taken greedily from the left, like [`str::replace`].
+    /// Since `self` does not keep its source, elements are recovered from the prefix hashes,
+    /// and `replacement` is also [reduced](Reduce).
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN* + *KR*), where *N* is `self.len()`, *K* is the number of occurrences and *R* is `replacement.len()`.
+    pub fn replace_all<T: Reduce<P>>(&self, needle: &[T], replacement: &[T]) -> Maybe<Vec<u64>> {
+        let mut result = Vec::with_capacity(self.len());
+        let mut certain = true;
+        let mut last = 0;
+        for position in self.positions(needle) {
+            if *position < last {
+                continue;
+            }
+            certain &= position.is_certain();
+            result.extend(self.residues(last..*position));
+            result.extend(replacement.iter().map(Reduce::reduce));
+            last = *position + needle.len();
+        }
+        result.extend(self.residues(last..self.len()));
+
+        if certain {
+            Maybe::certain(result)
+        } else {
+            Maybe::new(result)
+        }
+    }
+
+    /// Searches for windows of length `window` whose hash is `target`, returning all indexes.
+    ///
+    /// `target` should be computed with the same bases, e.g. by [`hash_of`](Self::hash_of).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` is `0`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN*), where *N* is `self.len()`.
+    pub fn positions_of_hash(&self, target: [u64; B], window: usize) -> Matches<'_, P, B> {
+        Matches::new(self.windows(window), target)
+    }
+
+    /// Searches for sub slice in `self`, collecting all indexes at once.
+    ///
+    /// An empty `slice` matches at every index in `0..=self.len()`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN*), where *N* is `self.len()`.
+    pub fn position_vec<T: Reduce<P>>(&self, slice: &[T]) -> Maybe<Vec<usize>> {
+        if slice.is_empty() {
+            return Maybe::certain((0..=self.len()).collect());
+        }
+        let target = self.hash_slice(slice);
+        Maybe::new(
+            self.windows(slice.len())
+                .enumerate()
+                .filter_map(|(i, sub_slice)| (sub_slice == target).then_some(i))
+                .collect(),
+        )
+    }
+
+    /// Counts sub slices in `self[range]`, that is, ones lying entirely in `range`.
+    ///
+    /// An empty `slice` is counted `range.len() + 1` times.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*(*M* + *R*)), where *M* is `slice.len()` and *R* is `range.len()`.
+    pub fn count_in_range<T: Reduce<P>>(&self, slice: &[T], range: Range<usize>) -> Maybe<usize> {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "range out of bounds"
+        );
+
+        if slice.is_empty() {
+            return Maybe::certain(range.len() + 1);
+        }
+        if slice.len() > range.len() {
+            return Maybe::certain(0);
+        }
+        let target = self.hash_slice(slice);
+        Maybe::new(
+            self.windows(slice.len())
+                .skip(range.start)
+                .take(range.len() - slice.len() + 1)
+                .filter(|sub_slice| sub_slice == &target)
+                .count(),
+        )
+    }
+
+    /// Counts sub slices in `self`.
+    ///
+    /// An empty `slice` is counted `self.len() + 1` times.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN*), where *N* is `self.len()`.
+    pub fn count<T: Reduce<P>>(&self, slice: &[T]) -> Maybe<usize> {
+        if slice.is_empty() {
+            return Maybe::certain(self.len() + 1);
+        }
+        if slice.len() > self.len() {
+            return Maybe::certain(0);
+        }
+        let target = self.hash_slice(slice);
+        Maybe::new(
+            self.windows(slice.len())
+                .filter(|sub_slice| sub_slice == &target)
+                .count(),
+        )
+    }
+}
+
+impl<const P: u64, const B: usize> Default for OneWay<P, B>
+where
+    Prime<P>: SupportedPrime,
+    BaseCount<B>: SupportedBaseCount,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const P: u64, const B: usize> Index<usize> for OneWay<P, B>
+where
+    Prime<P>: SupportedPrime,
+    BaseCount<B>: SupportedBaseCount,
+{
+    type Output = [u64; B];
+
+    /// Returns the hash of the prefix `[0, index]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rolling_hash::DefaultHasher;
+    ///
+    /// let hasher = DefaultHasher::from_slice(b"abab");
+    /// assert_eq!(hasher[0], hasher.hash_of(b"a"));
+    /// assert_eq!(hasher[2], hasher.hash_of(b"aba"));
+    /// assert_eq!(hasher[3], hasher.fingerprint());
+    /// ```
+    #[inline]
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.hash[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    const P: u64 = (1 << 61) - 1;
+    const BASES: [u64; 2] = [1_000_003, 998_244_353];
+    const BASES_8: [u64; 8] = [2, 3, 5, 7, 11, 13, 17, 19];
+
+    fn build<T: Reduce<P>>(slice: &[T]) -> OneWay<P, 2> {
+        OneWay::from_slice_with_bases(slice, BASES)
+    }
+
+    #[test]
+    fn position_vec_matches_positions() {
+        let hasher = build(b"abracadabra");
+        for needle in [&b"a"[..], b"abra", b"cad", b"x", b""] {
+            let positions: Vec<_> = hasher.positions(needle).map(|i| *i).collect();
+            assert_eq!(*hasher.position_vec(needle), positions);
+        }
+        assert_eq!(*hasher.position_vec(b"abra"), [0, 7]);
+    }
+
+    #[test]
+    fn position_verified_rejects_collision() {
+        // with equal bases `b`, both `[1, 0]` and `[0, b]` hash to `b`
+        let b = BASES[0];
+        let source = [1, 0, 0, b];
+        let hasher = OneWay::<P, 2>::from_slice_with_bases(&source, [b, b]);
+        let needle = [0, b];
+
+        assert_eq!(hasher.position(&needle).map(|i| *i), Some(0));
+        assert_eq!(hasher.position_verified(&source, &needle), Some(2));
+        assert_eq!(hasher.position_verified(&source, &[b, 0]), None);
+    }
+
+    #[test]
+    fn split_at_matches_direct_build() {
+        let source = b"abracadabra";
+        let hasher = build(source);
+        for mid in 0..=source.len() {
+            let (left, right) = hasher.split_at(mid);
+            assert_eq!(left.as_hashes(), build(&source[..mid]).as_hashes());
+            assert_eq!(right.as_hashes(), build(&source[mid..]).as_hashes());
+        }
+
+        let (left, right) = hasher.split_at(4);
+        assert_eq!(left.position(b"bra").map(|i| *i), Some(1));
+        assert_eq!(right.position(b"bra").map(|i| *i), Some(4));
+        assert_eq!(right.position(b"abr").map(|i| *i), Some(3));
+        assert!(left.position(b"cad").is_none());
+    }
+
+    #[test]
+    fn reversed_answers_reverse_queries() {
+        let reversed = build(b"abc").reversed();
+        assert_eq!(reversed.as_hashes(), build(b"cba").as_hashes());
+        assert_eq!(reversed.position(b"cb").map(|i| *i), Some(0));
+        assert_eq!(reversed.position(b"ba").map(|i| *i), Some(1));
+        assert!(reversed.position(b"bc").is_none());
+    }
+
+    #[test]
+    fn bases_compatible_only_with_shared_bases() {
+        let hasher = OneWay::<P, 2>::new();
+        assert!(hasher.bases_compatible(&hasher.clone()));
+        assert!(!hasher.bases_compatible(&OneWay::new()));
+        assert!(build(b"abc").bases_compatible(&build(b"xyz")));
+    }
+
+    #[test]
+    fn retain_matches_direct_build() {
+        let source: Vec<u64> = (0..20).map(|i| i * 7 % 11).collect();
+        let mut hasher = build(&source);
+        hasher.retain(|value| value % 2 == 0);
+
+        let filtered: Vec<_> = source
+            .iter()
+            .copied()
+            .filter(|value| value % 2 == 0)
+            .collect();
+        assert_eq!(hasher.as_hashes(), build(&filtered).as_hashes());
+    }
+
+    #[test]
+    fn insert_matches_direct_build() {
+        let source = [3, 1, 4, 1, 5];
+        for index in 0..=source.len() {
+            let mut hasher = build(&source);
+            hasher.insert(index, 9);
+
+            let mut edited = source.to_vec();
+            edited.insert(index, 9);
+            assert_eq!(hasher.as_hashes(), build(&edited).as_hashes());
+        }
+    }
+
+    #[test]
+    fn remove_matches_direct_build() {
+        let source = [3, 1, 4, 1, 5];
+        for index in 0..source.len() {
+            let mut hasher = build(&source);
+            assert_eq!(hasher.remove(index), source[index]);
+
+            let mut edited = source.to_vec();
+            edited.remove(index);
+            assert_eq!(hasher.as_hashes(), build(&edited).as_hashes());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "removal index should be < len")]
+    fn remove_out_of_bounds() {
+        build(&[1, 2]).remove(2);
+    }
+
+    #[test]
+    fn min_sketch_is_driven_by_shared_windows() {
+        let (a, b) = (build(b"abcabc"), build(b"xbcabc"));
+        let (sketch_a, sketch_b) = (a.min_sketch(2), b.min_sketch(2));
+
+        // both contain "ab", "bc" and "ca", and only `b` contains "xb"
+        let xb = b.hash_of(b"xb");
+        for i in 0..2 {
+            assert!(sketch_a[i] == sketch_b[i] || sketch_b[i] == xb[i]);
+        }
+        // the same set of windows gives the same sketch
+        assert_eq!(sketch_a, build(b"cabcab").min_sketch(2));
+        assert_eq!(build(b"a").min_sketch(2), [u64::MAX; 2]);
+    }
+
+    #[test]
+    fn jaccard_estimate_of_identical_and_disjoint() {
+        let a = OneWay::<P, 8>::from_slice_with_bases(b"the quick brown fox", BASES_8);
+        let b = OneWay::<P, 8>::from_slice_with_bases(b"the quick brown fox", BASES_8);
+        let c = OneWay::<P, 8>::from_slice_with_bases(b"jumps over lazy dogs", BASES_8);
+        assert_eq!(OneWay::jaccard_estimate(&a, &b, 3), 1.0);
+        assert_eq!(OneWay::jaccard_estimate(&a, &c, 3), 0.0);
+
+        // no windows to compare
+        let d = OneWay::<P, 8>::from_slice_with_bases(b"ab", BASES_8);
+        let e = OneWay::<P, 8>::from_slice_with_bases(b"xy", BASES_8);
+        assert_eq!(OneWay::jaccard_estimate(&d, &e, 3), 0.0);
+        assert_eq!(OneWay::jaccard_estimate(&a, &d, 3), 0.0);
+    }
+
+    #[test]
+    fn contains_agrees_with_position() {
+        let hasher = build(b"abracadabra");
+        for needle in [&b"abra"[..], b"cad", b"dab", b"bad", b"abracadabrax", b""] {
+            assert_eq!(*hasher.contains(needle), hasher.position(needle).is_some());
+        }
+    }
+
+    #[test]
+    fn starts_with_and_ends_with() {
+        let hasher = build(b"hello");
+        assert!(*hasher.starts_with(b"he"));
+        assert!(*hasher.starts_with(b"hello"));
+        assert!(*hasher.starts_with(b""));
+        assert!(!*hasher.starts_with(b"hex"));
+        assert!(!*hasher.starts_with(b"hello!"));
+
+        assert!(*hasher.ends_with(b"llo"));
+        assert!(*hasher.ends_with(b"hello"));
+        assert!(*hasher.ends_with(b""));
+        assert!(!*hasher.ends_with(b"lo!"));
+        assert!(!*hasher.ends_with(b"!hello"));
+    }
+
+    #[test]
+    fn from_slice_with_bases_is_deterministic() {
+        let (a, b) = (build(b"reproducible"), build(b"reproducible"));
+        assert_eq!(a.base(), b.base());
+        assert_eq!(a.as_hashes(), b.as_hashes());
+        assert_eq!(a.base(), &BASES);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid base")]
+    fn from_slice_with_bases_rejects_invalid_bases() {
+        let _ = OneWay::<P, 2>::from_slice_with_bases(b"abc", [2, P - 1]);
+    }
+
+    #[test]
+    fn distinct_windows_are_first_occurrences() {
+        assert_eq!(*build(b"abab").distinct_windows(2), [0..2, 1..3]);
+        assert_eq!(build(b"aaaa").distinct_windows(2).len(), 1);
+        assert_eq!(*build(b"abcab").distinct_windows(3), [0..3, 1..4, 2..5]);
+        assert!(build(b"a").distinct_windows(2).is_empty());
+    }
+
+    #[test]
+    fn degenerate_bases_are_rejected() {
+        for base in [0, 1, P - 1, P, P + 1] {
+            let result = std::panic::catch_unwind(|| OneWay::<P, 2>::with_base([base, 3]));
+            assert!(result.is_err(), "base {base} should be rejected");
+            assert_eq!(
+                OneWay::<P, 2>::from_raw([3, base], Vec::new(), false).err(),
+                Some(FromRawError::InvalidBase { index: 1 })
+            );
+        }
+        let hasher = OneWay::<P, 2>::new();
+        assert!(
+            hasher
+                .base()
+                .iter()
+                .all(|&base| Prime::<P>::is_probably_good_base(base))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid base")]
+    fn with_base_rejects_one() {
+        let _ = OneWay::<P, 2>::with_base([1; 2]);
+    }
+
+    #[test]
+    fn bulk_query_matches_position() {
+        let hasher = build(b"abracadabra");
+        let needles: [&[u8]; 7] = [b"bra", b"cad", b"abra", b"x", b"", b"dab", b"abracadabrax"];
+        let results: Vec<_> = hasher
+            .bulk_query(&needles)
+            .into_iter()
+            .map(|i| i.map(|i| *i))
+            .collect();
+        let expected: Vec<_> = needles
+            .iter()
+            .map(|needle| hasher.position(needle).map(|i| *i))
+            .collect();
+        assert_eq!(results, expected);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_positions_matches_positions() {
+        let source: Vec<u64> = (0..10_000).map(|i| i * i % 7).collect();
+        let hasher = build(&source);
+        for needle in [
+            &source[..3],
+            &source[100..104],
+            &[6, 6, 6][..],
+            &[],
+            &source,
+        ] {
+            let parallel: Vec<_> = hasher
+                .par_positions(needle)
+                .into_iter()
+                .map(|i| *i)
+                .collect();
+            let mut sequential: Vec<_> = hasher.positions(needle).map(|i| *i).collect();
+            sequential.sort_unstable();
+            assert_eq!(parallel, sequential);
+        }
+    }
+
+    #[test]
+    fn append_slice_keeps_source() {
+        let source = vec![3, 1, 4, 1, 5];
+        let mut by_slice = build::<u64>(&[]);
+        by_slice.append_slice(&source);
+        assert_eq!(source, [3, 1, 4, 1, 5]);
+
+        let mut drained = source.clone();
+        let mut by_vec = build::<u64>(&[]);
+        by_vec.append(&mut drained);
+        assert!(drained.is_empty());
+        assert_eq!(by_slice.as_hashes(), by_vec.as_hashes());
+    }
+
+    #[test]
+    fn extend_from_within_repeats_a_block() {
+        let mut hasher = build(b"abc");
+        hasher.extend_from_within(0..3);
+        hasher.extend_from_within(0..3);
+        assert_eq!(hasher.as_hashes(), build(b"abcabcabc").as_hashes());
+
+        hasher.extend_from_within(7..9);
+        assert_eq!(hasher.as_hashes(), build(b"abcabcabcbc").as_hashes());
+    }
+
+    #[test]
+    fn count_many_matches_count() {
+        let hasher = build(b"abababcab");
+        let needles: [&[u8]; 7] = [b"ab", b"ba", b"aba", b"abc", b"ab", b"", b"x"];
+        let counts: Vec<_> = hasher
+            .count_many(&needles)
+            .into_iter()
+            .map(|count| *count)
+            .collect();
+        let expected: Vec<_> = needles.iter().map(|needle| *hasher.count(needle)).collect();
+        assert_eq!(counts, expected);
+        assert_eq!(counts, [4, 2, 2, 1, 4, 10, 0]);
+    }
+
+    #[test]
+    fn rposition_before_respects_cutoff() {
+        // "ab" occurs at 0, 3, 6 and 9
+        let hasher = build(b"abcabcabcab");
+        let expected = [
+            None,
+            None,
+            Some(0),
+            Some(0),
+            Some(0),
+            Some(3),
+            Some(3),
+            Some(3),
+            Some(6),
+            Some(6),
+            Some(6),
+            Some(9),
+        ];
+        for (end, expected) in expected.into_iter().enumerate() {
+            assert_eq!(
+                hasher.rposition_before(b"ab", end).map(|i| *i),
+                expected,
+                "end = {end}"
+            );
+        }
+        assert_eq!(
+            hasher.rposition_before(b"abcabcabcabc", 11).map(|i| *i),
+            None
+        );
+        assert_eq!(hasher.rposition_before(b"", 4).map(|i| *i), Some(4));
+    }
+
+    #[test]
+    #[should_panic(expected = "end > len")]
+    fn rposition_before_out_of_bounds() {
+        let _ = build(b"ab").rposition_before(b"a", 3);
+    }
+
+    #[test]
+    fn position_after_resumes_scanning() {
+        // "ab" occurs at 0, 3, 6 and 9
+        let hasher = build(b"abcabcabcab");
+        let first = *hasher.position(b"ab").unwrap();
+        assert_eq!(hasher.position_after(b"ab", first + 1).map(|i| *i), Some(3));
+
+        let expected = [0, 3, 3, 3, 6, 6, 6, 9, 9, 9];
+        for (start, expected) in expected.into_iter().enumerate() {
+            assert_eq!(
+                hasher.position_after(b"ab", start).map(|i| *i),
+                Some(expected),
+                "start = {start}"
+            );
+        }
+        assert_eq!(hasher.position_after(b"ab", 10).map(|i| *i), None);
+        assert_eq!(hasher.position_after(b"ab", 11).map(|i| *i), None);
+        assert_eq!(hasher.position_after(b"ab", 100).map(|i| *i), None);
+
+        assert_eq!(hasher.position_after(b"", 11).map(|i| *i), Some(11));
+        assert_eq!(hasher.position_after(b"", 12).map(|i| *i), None);
+    }
+
+    #[test]
+    fn equal_sequences_have_equal_fingerprints() {
+        let a = build(b"the quick brown fox");
+        let b = build(b"the quick brown fox");
+        assert_eq!(a.fingerprint(), b.fingerprint());
+        assert_eq!(a.fingerprint_u64(), b.fingerprint_u64());
+        assert_eq!(a.fingerprint(), a.hash_of(b"the quick brown fox"));
+
+        let c = build(b"the quick brown fix");
+        assert_ne!(a.fingerprint(), c.fingerprint());
+        assert_eq!(build::<u8>(&[]).fingerprint(), [0; 2]);
+    }
+
+    #[test]
+    fn equals_slice_compares_lengths_first() {
+        let hasher = build(&[0u64, 0, 1, 2]);
+        assert!(*hasher.equals_slice(&[0u64, 0, 1, 2]));
+        assert!(!*hasher.equals_slice(&[0u64, 0, 1, 3]));
+        assert!(!*hasher.equals_slice(&[0u64, 0, 1]));
+
+        // leading zeros do not change the hash, so only the length tells these apart
+        assert_eq!(hasher.fingerprint(), hasher.hash_of(&[1u64, 2]));
+        assert!(!*hasher.equals_slice(&[1u64, 2]));
+        assert!(*build::<u64>(&[]).equals_slice::<u64>(&[]));
+    }
+
+    #[test]
+    fn collision_probability_is_monotone() {
+        assert_eq!(OneWay::<P, 2>::collision_probability(0), 0.0);
+        let mut last = 0.0;
+        for n in (0..64).map(|exp| 1u64 << exp).chain([u64::MAX]) {
+            let probability = OneWay::<P, 2>::collision_probability(n);
+            assert!(last < probability && probability <= 1.0, "n = {n}");
+            assert!(
+                OneWay::<P, 3>::collision_probability(n) < probability,
+                "n = {n}"
+            );
+            last = probability;
+        }
+    }
+
+    #[test]
+    fn positions_of_hash_agrees_with_positions() {
+        let hasher = build(b"abcabcabcab");
+        let target = hasher.hash_of(b"bca");
+        let positions: Vec<_> = hasher.positions_of_hash(target, 3).map(|i| *i).collect();
+        assert_eq!(positions, [1, 4, 7]);
+        assert!(hasher.positions(b"bca").map(|i| *i).eq(positions));
+        assert_eq!(hasher.positions_of_hash(target, 4).count(), 0);
+    }
+
+    #[test]
+    fn hash_reduced_agrees_with_hash_of() {
+        let hasher = build::<u64>(&[]);
+        let raw = [P, P + 1, u64::MAX, 0, 42, 2 * P - 1];
+        let reduced = raw.map(|value| value % P);
+        assert_eq!(hasher.hash_reduced(reduced), hasher.hash_of(&raw));
+        assert_eq!(hasher.hash_reduced(reduced), hasher.hash_of(&reduced));
+    }
+
+    #[test]
+    fn hash_ranges_matches_hash_range() {
+        let hasher = build(b"mississippi");
+        let ranges = [0..4, 1..5, 4..8, 3..3, 0..11, 2..5, 7..11, 10..11, 11..11];
+        let expected: Vec<_> = ranges
+            .iter()
+            .map(|range| hasher.hash_range(range.clone()))
+            .collect();
+        assert_eq!(hasher.hash_ranges(ranges), expected);
+        assert_eq!(hasher.hash_ranges([]), Vec::<[u64; 2]>::new());
+    }
+
+    #[test]
+    fn compare_suffixes_sorts_like_brute_force() {
+        let slice = b"banana";
+        let hasher = build(slice);
+        let mut by_hash: Vec<_> = (0..slice.len()).collect();
+        by_hash.sort_by(|&i, &j| *hasher.compare_suffixes(i, j));
+        let mut brute_force: Vec<_> = (0..slice.len()).collect();
+        brute_force.sort_by_key(|&i| &slice[i..]);
+        assert_eq!(by_hash, brute_force);
+
+        assert_eq!(*hasher.compare_suffixes(1, 3), Ordering::Greater);
+        assert_eq!(*hasher.compare_suffixes(5, 3), Ordering::Less);
+        assert_eq!(*hasher.compare_suffixes(2, 2), Ordering::Equal);
+        assert_eq!(*hasher.compare_suffixes(6, 0), Ordering::Less);
+
+        let mut rng = StdRng::seed_from_u64(355);
+        for _ in 0..100 {
+            let len = rng.random_range(0..20);
+            let slice: Vec<u64> = (0..len).map(|_| rng.random_range(0..3)).collect();
+            let hasher = build(&slice);
+            for i in 0..=len {
+                for j in 0..=len {
+                    assert_eq!(*hasher.compare_suffixes(i, j), slice[i..].cmp(&slice[j..]));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn suffix_array_of_banana() {
+        assert_eq!(*build(b"banana").suffix_array(), [5, 3, 1, 0, 4, 2]);
+        assert_eq!(*build::<u8>(&[]).suffix_array(), Vec::<usize>::new());
+
+        let mut rng = StdRng::seed_from_u64(356);
+        for _ in 0..100 {
+            let len = rng.random_range(0..30);
+            let slice: Vec<u64> = (0..len).map(|_| rng.random_range(0..3)).collect();
+            let mut brute_force: Vec<_> = (0..len).collect();
+            brute_force.sort_by_key(|&i| &slice[i..]);
+            assert_eq!(*build(&slice).suffix_array(), brute_force);
+        }
+    }
+
+    #[test]
+    fn z_array_matches_brute_force() {
+        assert_eq!(*build(b"aabxaab").z_array(), [7, 1, 0, 0, 3, 1, 0]);
+
+        let mut rng = StdRng::seed_from_u64(358);
+        for _ in 0..100 {
+            let len = rng.random_range(0..30);
+            let slice: Vec<u64> = (0..len).map(|_| rng.random_range(0..2)).collect();
+            let brute_force: Vec<_> = (0..len)
+                .map(|i| {
+                    slice
+                        .iter()
+                        .zip(&slice[i..])
+                        .take_while(|(a, b)| a == b)
+                        .count()
+                })
+                .collect();
+            assert_eq!(*build(&slice).z_array(), brute_force);
+        }
+    }
+
+    #[test]
+    fn common_prefix_len_stops_at_divergence() {
+        let mut slice: Vec<u64> = (0..1000).collect();
+        slice.extend([1, 2, 3]);
+        let hasher = build(&slice);
+
+        let mut diverging: Vec<u64> = (0..1000).collect();
+        diverging.extend([1, 5, 3]);
+        assert_eq!(*hasher.common_prefix_len(&diverging), 1001);
+        diverging[1000] = 0;
+        assert_eq!(*hasher.common_prefix_len(&diverging), 1000);
+        diverging[0] = 1;
+        assert_eq!(*hasher.common_prefix_len(&diverging), 0);
+
+        assert_eq!(*hasher.common_prefix_len(&slice[..500]), 500);
+        slice.push(4);
+        assert_eq!(*hasher.common_prefix_len(&slice), 1003);
+        assert_eq!(*hasher.common_prefix_len::<u64>(&[]), 0);
+    }
+
+    #[test]
+    fn from_raw_round_trips() {
+        let hasher = build(b"round trip");
+        for validate in [false, true] {
+            let rebuilt =
+                OneWay::<P, 2>::from_raw(*hasher.base(), hasher.as_hashes().to_vec(), validate)
+                    .unwrap();
+            assert_eq!(rebuilt.as_hashes(), hasher.as_hashes());
+            assert_eq!(rebuilt.position(b"trip").map(|i| *i), Some(6));
+        }
+
+        assert_eq!(
+            OneWay::<P, 2>::from_raw([BASES[0], 1], Vec::new(), false).err(),
+            Some(FromRawError::InvalidBase { index: 1 })
+        );
+        let mut hash = hasher.as_hashes().to_vec();
+        hash[3][1] = Prime::<P>::add_mod(hash[3][1], 1);
+        assert!(OneWay::<P, 2>::from_raw(*hasher.base(), hash.clone(), false).is_ok());
+        assert_eq!(
+            OneWay::<P, 2>::from_raw(*hasher.base(), hash, true).err(),
+            Some(FromRawError::InconsistentHash { index: 3 })
+        );
+    }
+
+    #[test]
+    fn empty_needle_matches_everywhere() {
+        let empty: &[u8] = &[];
+        for haystack in [&b""[..], b"abc"] {
+            let hasher = build(haystack);
+            let len = haystack.len();
+            let everywhere: Vec<_> = (0..=len).collect();
+
+            let position = hasher.position(empty).unwrap();
+            assert_eq!(*position, 0);
+            assert!(position.is_certain());
+            assert_eq!(hasher.rposition(empty).map(|i| *i), Some(len));
+            assert_eq!(
+                hasher.positions(empty).map(|i| *i).collect::<Vec<_>>(),
+                everywhere
+            );
+            assert!(hasher.matches(empty).rev().map(|i| *i).eq((0..=len).rev()));
+            assert_eq!(*hasher.position_vec(empty), everywhere);
+            assert_eq!(*hasher.count(empty), len + 1);
+            assert_eq!(*hasher.count_in_range(empty, 0..len), len + 1);
+            assert!(*hasher.contains(empty));
+            assert!(*hasher.starts_with(empty));
+            assert!(*hasher.ends_with(empty));
+            assert_eq!(hasher.position_after(empty, len).map(|i| *i), Some(len));
+            assert_eq!(hasher.rposition_before(empty, 0).map(|i| *i), Some(0));
+            assert_eq!(
+                hasher.bulk_query(&[empty])[0].as_ref().map(|i| **i),
+                Some(0)
+            );
+            assert_eq!(*hasher.count_many(&[empty])[0], len + 1);
+        }
+    }
+
+    #[test]
+    fn too_long_needle_matches_nowhere() {
+        let hasher = build(b"abc");
+        for needle in [&b"abcd"[..], b"xxxxxxxx"] {
+            assert_eq!(hasher.position(needle).map(|i| *i), None);
+            assert_eq!(hasher.rposition(needle).map(|i| *i), None);
+            assert_eq!(hasher.positions(needle).count(), 0);
+            assert_eq!(hasher.matches(needle).next_back().map(|i| *i), None);
+            // returned early, without comparing any hashes
+            let count = hasher.count(needle);
+            assert_eq!(*count, 0);
+            assert!(count.is_certain());
+        }
+        assert_eq!(build::<u8>(&[]).position(b"a").map(|i| *i), None);
+    }
+
+    #[test]
+    fn extend_exact_reserves_once() {
+        let values: Vec<u64> = (0..100).collect();
+        let mut hasher = build::<u64>(&[]);
+        hasher.extend_exact(values.iter().copied());
+        // pushing one by one would double the capacity up to 128
+        assert!((100..128).contains(&hasher.hash.capacity()));
+        assert_eq!(hasher.as_hashes(), build(&values).as_hashes());
+
+        let ptr = hasher.hash.as_ptr();
+        let capacity = hasher.hash.capacity();
+        hasher.extend_exact(std::iter::empty::<u64>());
+        assert_eq!(
+            (hasher.hash.as_ptr(), hasher.hash.capacity()),
+            (ptr, capacity)
+        );
+    }
+
+    #[test]
+    fn positions_tagged_merges_in_order() {
+        let hasher = build(b"abcabcab");
+        let needles: [&[u8]; 2] = [b"ca", b"ab"];
+        let tagged: Vec<_> = hasher
+            .positions_tagged(&needles)
+            .map(|(id, i)| (id, *i))
+            .collect();
+        assert_eq!(tagged, [(1, 0), (0, 2), (1, 3), (0, 5), (1, 6)]);
+
+        // ties are broken by the needle index
+        let needles: [&[u8]; 3] = [b"abc", b"a", b"zz"];
+        let tagged: Vec<_> = hasher
+            .positions_tagged(&needles)
+            .map(|(id, i)| (id, *i))
+            .collect();
+        assert_eq!(tagged, [(0, 0), (1, 0), (0, 3), (1, 3), (1, 6)]);
+    }
+
+    #[test]
+    fn replace_all_rewrites_occurrences() {
+        let residues = |bytes: &[u8]| bytes.iter().map(|&b| b as u64).collect::<Vec<_>>();
+        let hasher = build(b"abcab");
+        let replaced = hasher.replace_all(&residues(b"ab"), &residues(b"xyz"));
+        assert_eq!(*replaced, residues(b"xyzcxyz"));
+        assert!(!replaced.is_certain());
+
+        // non-overlapping, from the left
+        let hasher = build(b"aaaaa");
+        assert_eq!(
+            *hasher.replace_all(&residues(b"aa"), &residues(b"b")),
+            residues(b"bba")
+        );
+        assert_eq!(
+            *hasher.replace_all(&residues(b"x"), &residues(b"y")),
+            residues(b"aaaaa")
+        );
+        assert_eq!(
+            *build(b"ab").replace_all(&[], &residues(b"-")),
+            residues(b"-a-b-")
+        );
+    }
+
+    #[test]
+    fn count_in_range_counts_windows_inside() {
+        let hasher = build(b"aaaaa");
+        assert_eq!(*hasher.count_in_range(b"aa", 0..5), 4);
+        assert_eq!(*hasher.count_in_range(b"aa", 1..4), 2);
+        assert_eq!(*hasher.count_in_range(b"aa", 2..3), 0);
+        assert_eq!(*hasher.count_in_range(b"aa", 3..3), 0);
+        assert_eq!(*hasher.count_in_range(b"b", 0..5), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "range out of bounds")]
+    fn count_in_range_out_of_bounds() {
+        let _ = build(b"aaaaa").count_in_range(b"aa", 2..6);
+    }
+
+    #[test]
+    fn dedup_matches_direct_build() {
+        let mut hasher = build(b"aabbbc");
+        hasher.dedup();
+        assert_eq!(hasher.as_hashes(), build(b"abc").as_hashes());
+
+        let mut hasher = build(b"abab");
+        hasher.dedup();
+        assert_eq!(hasher.as_hashes(), build(b"abab").as_hashes());
+        let mut empty = build::<u8>(&[]);
+        empty.dedup();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn rotate_matches_direct_build() {
+        let source = *b"abcde";
+        for k in 0..=source.len() {
+            let mut rotated = source;
+            rotated.rotate_left(k);
+            let mut hasher = build(&source);
+            hasher.rotate_left(k);
+            assert_eq!(hasher.as_hashes(), build(&rotated).as_hashes(), "k = {k}");
+
+            let mut rotated = source;
+            rotated.rotate_right(k);
+            let mut hasher = build(&source);
+            hasher.rotate_right(k);
+            assert_eq!(hasher.as_hashes(), build(&rotated).as_hashes(), "k = {k}");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "mid > len")]
+    fn rotate_left_out_of_bounds() {
+        build(b"abcde").rotate_left(6);
+    }
+
+    #[test]
+    fn least_rotation_matches_brute_force() {
+        // rotations of "bca" are "bca", "cab" and "abc"
+        assert_eq!(*build(b"bca").least_rotation(), 2);
+        // ties go to the smallest index
+        assert_eq!(*build(b"abab").least_rotation(), 0);
+        assert_eq!(*build::<u8>(&[]).least_rotation(), 0);
+
+        let mut rng = StdRng::seed_from_u64(386);
+        for _ in 0..100 {
+            let len = rng.random_range(1..20);
+            let slice: Vec<u64> = (0..len).map(|_| rng.random_range(0..3)).collect();
+            let rotation = |i: usize| [&slice[i..], &slice[..i]].concat();
+            let brute_force = (0..len).min_by_key(|&i| rotation(i)).unwrap();
+            assert_eq!(*build(&slice).least_rotation(), brute_force, "{slice:?}");
+        }
+    }
+
+    #[test]
+    fn position_with_mismatches_matches_brute_force() {
+        let hasher = build(b"zzaxczabd");
+        assert_eq!(hasher.position_with_mismatches(b"abc", 0).map(|i| *i), None);
+        // "axc" at 2 and "abd" at 6
+        assert_eq!(
+            hasher.position_with_mismatches(b"abc", 1).map(|i| *i),
+            Some(2)
+        );
+        assert_eq!(
+            build(b"abd")
+                .position_with_mismatches(b"abc", 1)
+                .map(|i| *i),
+            Some(0)
+        );
+        assert_eq!(
+            hasher.position_with_mismatches(b"abc", 3).map(|i| *i),
+            Some(0)
+        );
+
+        let mut rng = StdRng::seed_from_u64(387);
+        for _ in 0..100 {
+            let slice: Vec<u64> = (0..rng.random_range(0..20))
+                .map(|_| rng.random_range(0..3))
+                .collect();
+            let needle: Vec<u64> = (0..rng.random_range(1..6))
+                .map(|_| rng.random_range(0..3))
+                .collect();
+            let k = rng.random_range(0..3);
+            let brute_force = slice
+                .windows(needle.len())
+                .position(|window| window.iter().zip(&needle).filter(|(a, b)| a != b).count() <= k);
+            let found = build(&slice)
+                .position_with_mismatches(&needle, k)
+                .map(|i| *i);
+            assert_eq!(found, brute_force, "{slice:?}, {needle:?}, k = {k}");
+        }
+    }
+
+    #[test]
+    fn push_chars_and_i32s() {
+        let mut chars = build::<char>(&[]);
+        for c in "héllo".chars() {
+            chars.push(c);
+        }
+        let code_points: Vec<u32> = "héllo".chars().map(|c| c as u32).collect();
+        assert_eq!(chars.as_hashes(), build(&code_points).as_hashes());
+
+        let mut ints = build::<i32>(&[]);
+        ints.push(-1i32);
+        ints.append(&mut vec![2i32, -3]);
+        // negative values are reduced to their non-negative residues
+        assert_eq!(ints.as_hashes(), build(&[P - 1, 2, P - 3]).as_hashes());
+        assert_eq!(ints.position(&[2i32, -3]).map(|i| *i), Some(1));
+    }
+
+    #[test]
+    fn generic_needles_and_edits() {
+        let text = "naïve café";
+        let chars: Vec<char> = text.chars().collect();
+        let hasher = build(&chars);
+        let needle: Vec<char> = "café".chars().collect();
+        assert_eq!(hasher.position(&needle).map(|i| *i), Some(6));
+        assert_eq!(hasher.position_verified(&chars, &needle), Some(6));
+        assert_eq!(hasher.position_verified(&chars, &['x']), None);
+
+        let replaced = hasher.replace_all(&['ï'], &['i']);
+        let expected: Vec<u64> = "naive café".chars().map(|c| c as u64).collect();
+        assert_eq!(*replaced, expected);
+
+        let mut edited = build(&chars[..5]);
+        edited.append_slice(&chars[6..]);
+        edited.insert(5, ' ');
+        assert_eq!(edited.as_hashes(), hasher.as_hashes());
+    }
+
+    #[test]
+    fn search_phrase_in_words() {
+        let sentence = "the cat sat on the mat and the cat ran";
+        let words: Vec<&str> = sentence.split_whitespace().collect();
+        let hasher = build(&words);
+        assert_eq!(hasher.len(), 10);
+        assert!(hasher.positions(&["the", "cat"]).map(|i| *i).eq([0, 7]));
+        assert_eq!(hasher.position(&["on", "the"]).map(|i| *i), Some(3));
+        assert_eq!(hasher.position(&["cat", "the"]).map(|i| *i), None);
+        // a whole word is a single element, unlike its characters
+        assert_eq!(hasher.position(&["th", "e"]).map(|i| *i), None);
+    }
+
+    #[test]
+    fn contains_run_of_counts_identical_elements() {
+        let hasher = build(b"aaab");
+        assert!(*hasher.contains_run_of(b'a', 3));
+        assert!(!*hasher.contains_run_of(b'a', 4));
+        assert!(*hasher.contains_run_of(b'b', 1));
+        assert!(!*hasher.contains_run_of(b'b', 2));
+        // `'a' + P` is reduced to `'a'`
+        assert!(*hasher.contains_run_of(b'a' as u64 + P, 2));
+
+        let empty = hasher.contains_run_of(b'c', 0);
+        assert!(*empty && empty.is_certain());
+        let too_long = hasher.contains_run_of(b'a', 5);
+        assert!(!*too_long && too_long.is_certain());
+    }
+
+    #[test]
+    fn first_difference_finds_a_single_change() {
+        let slice: Vec<u64> = (0..1000).collect();
+        let mut changed = slice.clone();
+        changed[500] = 0;
+        let (a, b) = (build(&slice), build(&changed));
+        assert_eq!(OneWay::first_difference(&a, &b).map(|i| *i), Some(500));
+        assert_eq!(OneWay::first_difference(&b, &a).map(|i| *i), Some(500));
+
+        assert_eq!(OneWay::first_difference(&a, &a.clone()).map(|i| *i), None);
+        // a proper prefix differs at its end
+        let prefix = build(&slice[..700]);
+        assert_eq!(OneWay::first_difference(&a, &prefix).map(|i| *i), Some(700));
+        assert_eq!(
+            OneWay::first_difference(&build::<u64>(&[]), &a).map(|i| *i),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn common_suffix_len_of_shared_tail() {
+        let suffix: Vec<u64> = (1000..1100).collect();
+        let a: Vec<u64> = (0..300).chain(suffix.iter().copied()).collect();
+        let b: Vec<u64> = (500..550).chain(suffix.iter().copied()).collect();
+        let (a, b) = (build(&a), build(&b));
+        assert_eq!(*OneWay::common_suffix_len(&a, &b), 100);
+        assert_eq!(*OneWay::common_suffix_len(&b, &a), 100);
+
+        // the shorter one is a suffix of the other
+        let tail = build(&suffix[40..]);
+        assert_eq!(*OneWay::common_suffix_len(&a, &tail), 60);
+        assert_eq!(*OneWay::common_suffix_len(&a, &build::<u64>(&[])), 0);
+        assert_eq!(*OneWay::common_suffix_len(&a, &build(&[7u64])), 0);
     }
 }
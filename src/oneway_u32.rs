@@ -0,0 +1,375 @@
+use std::{num::NonZero, ops::Range};
+
+use crate::{
+    BaseCount, Matches, Maybe, ModArith, PrimeU32, Reduce, SupportedBaseCount, SupportedPrimeU32,
+    Windows, chain,
+};
+
+/// Compact variant of [`OneWay`](crate::OneWay) with a 32-bit prime number and `u32` lanes.
+///
+/// Memory usage is halved, but collisions are much more likely, about `1 / P^B` for each comparison.
+#[derive(Clone)]
+pub struct OneWayU32<const P: u64, const B: usize>
+where
+    PrimeU32<P>: SupportedPrimeU32,
+    BaseCount<B>: SupportedBaseCount,
+{
+    base: [u32; B],
+    hash: Vec<[u32; B]>,
+}
+
+impl<const P: u64, const B: usize> OneWayU32<P, B>
+where
+    PrimeU32<P>: SupportedPrimeU32,
+    BaseCount<B>: SupportedBaseCount,
+{
+    /// Creates a new instance.
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_base(std::array::from_fn(|_| {
+            rand::random_range(2..=P as u32 - 2)
+        }))
+    }
+
+    /// Creates a new instance with specified bases.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of bases are `0`, `1` or `P - 1` modulo `P`.
+    pub fn with_base(mut base: [u32; B]) -> Self {
+        for base in &mut base {
+            *base %= P as u32;
+            assert!(
+                2 <= *base && *base <= P as u32 - 2,
+                "invalid base: base should be in 2..=P - 2"
+            );
+        }
+
+        Self {
+            base,
+            hash: Vec::new(),
+        }
+    }
+
+    /// Creates a new instance from `slice`, where each element is [reduced](Reduce).
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN*), where *N* is `slice.len()`.
+    pub fn from_slice<T: Reduce<P>>(slice: &[T]) -> Self {
+        let mut hasher = Self::new();
+        hasher.hash.reserve(slice.len());
+        for value in slice {
+            hasher.push(value);
+        }
+        hasher
+    }
+
+    /// Creates a new instance from `slice` with specified bases, where each element is [reduced](Reduce).
+    ///
+    /// # Panics
+    ///
+    /// See [`with_base`](Self::with_base).
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN*), where *N* is `slice.len()`.
+    pub fn from_slice_with_bases<T: Reduce<P>>(slice: &[T], bases: [u32; B]) -> Self {
+        let mut hasher = Self::with_base(bases);
+        hasher.hash.reserve(slice.len());
+        for value in slice {
+            hasher.push(value);
+        }
+        hasher
+    }
+
+    /// Returns the number of elements in `self`.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.hash.len()
+    }
+
+    /// Returns `true` if `self` has a length of 0, and `false` otherwise.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.hash.is_empty()
+    }
+
+    /// Returns bases.
+    pub fn base(&self) -> &[u32; B] {
+        &self.base
+    }
+
+    /// Returns the prefix hashes, where the `i`-th one is the hash of the first `i + 1` elements.
+    #[inline]
+    pub fn as_hashes(&self) -> &[[u32; B]] {
+        &self.hash
+    }
+
+    /// Hashes `next` following `prev`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*)
+    #[inline]
+    fn hash_next(&self, prev: &[u32; B], next: u32) -> [u32; B] {
+        chain::hash_next::<PrimeU32<P>, B>(&self.base, prev, next)
+    }
+
+    /// Appends an element to the back of `self`, where the element is [reduced](Reduce).
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*)
+    pub fn push<T: Reduce<P>>(&mut self, value: T) {
+        let prev = self.hash.last().copied().unwrap_or([0; B]);
+        let next = self.hash_next(&prev, PrimeU32::<P>::from_residue(value.reduce()));
+        self.hash.push(next);
+    }
+
+    /// Returns the hash of `slice`, where each element is [reduced](Reduce).
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BM*), where *M* is `slice.len()`.
+    pub fn hash_of<T: Reduce<P>>(&self, slice: &[T]) -> [u32; B] {
+        slice.iter().fold([0; B], |prev, next| {
+            self.hash_next(&prev, PrimeU32::<P>::from_residue(next.reduce()))
+        })
+    }
+
+    /// Returns `base^len` for each base.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B* log *len*)
+    fn base_pow(&self, len: usize) -> [u32; B] {
+        chain::base_pow::<PrimeU32<P>, B>(&self.base, len)
+    }
+
+    /// Returns the hash of the sub slice in `range`, where `base_pow_len` is `base^range.len()`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*)
+    fn hash_range_with(&self, range: Range<usize>, base_pow_len: &[u32; B]) -> [u32; B] {
+        chain::hash_range_with::<PrimeU32<P>, B>(&self.hash, range, base_pow_len)
+    }
+
+    /// Returns the hash of the sub slice in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B* log *M*), where *M* is `range.len()`.
+    pub fn hash_range(&self, range: Range<usize>) -> [u32; B] {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "range out of bounds"
+        );
+
+        let base_pow_len = self.base_pow(range.len());
+        self.hash_range_with(range, &base_pow_len)
+    }
+
+    /// Returns an iterator over hashes of all contiguous windows of length `size`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is `0`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*)
+    pub fn windows(&self, size: usize) -> Windows<'_, P, B, PrimeU32<P>> {
+        let size = NonZero::new(size).expect("slice must not be empty");
+        Windows::new(&self.hash, self.base, size)
+    }
+
+    /// Searches for an sub slice in `self`, returning its index.
+    ///
+    /// An empty `slice` matches at every index in `0..=self.len()`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN*), where *N* is `self.len()`.
+    #[must_use]
+    pub fn position<T: Reduce<P>>(&self, slice: &[T]) -> Option<Maybe<usize>> {
+        if slice.is_empty() {
+            return Some(Maybe::certain(0));
+        }
+        if slice.len() > self.len() {
+            return None;
+        }
+        let target = self.hash_of(slice);
+        self.windows(slice.len())
+            .position(|sub_slice| sub_slice == target)
+            .map(Maybe::new)
+    }
+
+    /// Searches for sub slice in `self` from the right, returning its index.
+    ///
+    /// An empty `slice` matches at every index in `0..=self.len()`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN*), where *N* is `self.len()`.
+    #[must_use]
+    pub fn rposition<T: Reduce<P>>(&self, slice: &[T]) -> Option<Maybe<usize>> {
+        if slice.is_empty() {
+            return Some(Maybe::certain(self.len()));
+        }
+        if slice.len() > self.len() {
+            return None;
+        }
+        let target = self.hash_of(slice);
+        self.windows(slice.len())
+            .rposition(|sub_slice| sub_slice == target)
+            .map(Maybe::new)
+    }
+
+    /// Searches for sub slice in `self`, returning all indexes.
+    ///
+    /// An empty `slice` matches at every index in `0..=self.len()`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN*), where *N* is `self.len()`.
+    pub fn positions<T: Reduce<P>>(&self, slice: &[T]) -> impl Iterator<Item = Maybe<usize>> {
+        self.matches(slice)
+    }
+
+    /// Searches for sub slice in `self`, returning all indexes from either end.
+    ///
+    /// An empty `slice` matches at every index in `0..=self.len()`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN*), where *N* is `self.len()`.
+    pub fn matches<T: Reduce<P>>(&self, slice: &[T]) -> Matches<'_, P, B, PrimeU32<P>> {
+        if slice.is_empty() {
+            return Matches::everywhere(self.len() + 1);
+        }
+        if slice.len() > self.len() {
+            // no windows, so the target is never compared
+            return Matches::new(self.windows(slice.len()), [0; B]);
+        }
+        Matches::new(self.windows(slice.len()), self.hash_of(slice))
+    }
+
+    /// Counts sub slices in `self`.
+    ///
+    /// An empty `slice` is counted `self.len() + 1` times.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN*), where *N* is `self.len()`.
+    pub fn count<T: Reduce<P>>(&self, slice: &[T]) -> Maybe<usize> {
+        if slice.is_empty() {
+            return Maybe::certain(self.len() + 1);
+        }
+        if slice.len() > self.len() {
+            return Maybe::certain(0);
+        }
+        let target = self.hash_of(slice);
+        Maybe::new(
+            self.windows(slice.len())
+                .filter(|sub_slice| sub_slice == &target)
+                .count(),
+        )
+    }
+}
+
+impl<const P: u64, const B: usize> Default for OneWayU32<P, B>
+where
+    PrimeU32<P>: SupportedPrimeU32,
+    BaseCount<B>: SupportedBaseCount,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{OneWay, PRIMES_U32};
+
+    const P: u64 = PRIMES_U32[0];
+    const BASES: [u32; 2] = [1_000_003, 998_244_353];
+
+    #[test]
+    fn search_agrees_with_oneway() {
+        let slice = b"abracadabra abracadabra";
+        let hasher = OneWayU32::<P, 2>::from_slice_with_bases(slice, BASES);
+        let wide = OneWay::<{ (1 << 61) - 1 }, 2>::from_slice(slice);
+        for needle in [
+            &b"abra"[..],
+            b"a",
+            b"cad",
+            b"ra a",
+            b"abracadabra abracadabra",
+        ] {
+            assert_eq!(
+                hasher.positions(needle).map(|i| *i).collect::<Vec<_>>(),
+                wide.positions(needle).map(|i| *i).collect::<Vec<_>>()
+            );
+            assert_eq!(
+                hasher.position(needle).map(|i| *i),
+                wide.position(needle).map(|i| *i)
+            );
+            assert_eq!(
+                hasher.rposition(needle).map(|i| *i),
+                wide.rposition(needle).map(|i| *i)
+            );
+            assert_eq!(*hasher.count(needle), *wide.count(needle));
+        }
+        assert_eq!(hasher.rposition(b"abra").map(|i| *i), Some(19));
+        assert_eq!(
+            hasher
+                .matches(b"abra")
+                .rev()
+                .map(|i| *i)
+                .collect::<Vec<_>>(),
+            [19, 12, 7, 0]
+        );
+    }
+
+    #[test]
+    fn signed_elements_are_reduced() {
+        let hasher = OneWayU32::<P, 2>::from_slice_with_bases(&[-1i32, 5, -1, 5], BASES);
+        assert_eq!(hasher.hash_of(&[-1i32]), hasher.hash_of(&[P - 1]));
+        assert_eq!(hasher.rposition(&[-1i32, 5]).map(|i| *i), Some(2));
+        assert_eq!(hasher.hash_range(1..3), hasher.hash_of(&[5i64, -1]));
+    }
+
+    #[test]
+    fn empty_and_too_long_needles() {
+        let hasher = OneWayU32::<P, 2>::from_slice_with_bases(b"abc", BASES);
+        let empty: &[u8] = &[];
+
+        let first = hasher.position(empty).unwrap();
+        assert_eq!(*first, 0);
+        assert!(first.is_certain());
+        assert_eq!(hasher.rposition(empty).map(|i| *i), Some(3));
+        assert!(hasher.positions(empty).map(|i| *i).eq(0..=3));
+        let count = hasher.count(empty);
+        assert_eq!(*count, 4);
+        assert!(count.is_certain());
+
+        assert!(hasher.position(b"abcd").is_none());
+        assert!(hasher.rposition(b"abcd").is_none());
+        assert_eq!(hasher.matches(b"abcd").count(), 0);
+        assert_eq!(*hasher.count(b"abcd"), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "slice must not be empty")]
+    fn windows_of_size_zero_panics() {
+        let hasher = OneWayU32::<P, 2>::from_slice_with_bases(b"abc", BASES);
+        let _ = hasher.windows(0);
+    }
+}
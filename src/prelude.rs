@@ -0,0 +1,15 @@
+//! Re-exports of the commonly used items, intended to be glob imported by `use rolling_hash::prelude::*;`.
+//!
+//! # Examples
+//!
+//! ```
+//! use rolling_hash::prelude::*;
+//!
+//! let hasher = OneWay::<{ PRIMES[9] }, 2>::from_slice(b"hello world");
+//! let found: Option<Maybe<usize>> = hasher.position(b"world");
+//! assert_eq!(found.map(|i| *i), Some(6));
+//! ```
+pub use crate::{
+    BaseCount, BaseSet, BidirectionalRollingHash, DefaultHasher, FromRawError, Maybe, OneWay,
+    PRIMES, Prime, Reduce, StrongHasher, SupportedBaseCount, SupportedPrime,
+};
@@ -1,7 +1,10 @@
-/// Specified prime number that is suitable for [`RollingHasher`].
+use std::fmt;
+
+/// Specified prime number that is suitable for [`OneWay`](crate::OneWay).
+#[derive(Debug, Clone, Copy)]
 pub struct Prime<const P: u64>;
 
-/// A marker trait for prime numbers that are suitable for [`RollingHasher`].
+/// A marker trait for prime numbers that are suitable for [`OneWay`](crate::OneWay).
 /*
 ! # Constraints
 !
@@ -12,7 +15,7 @@ pub trait SupportedPrime {}
 
 macro_rules! supported_prime_impl {
     ($n:literal; $( (1 << $exp:literal) - $diff:literal),*$(,)?) => {
-        /// Large prime numbers that is suitable for [`RollingHasher`].
+        /// Large prime numbers that is suitable for [`OneWay`](crate::OneWay).
         pub const PRIMES: [u64; $n] = [$( { (1 << $exp) - $diff } ),*];
 
         $(
@@ -45,121 +48,338 @@ supported_prime_impl! {
     (1 << 61) - 1,
 }
 
+/// Multiplication, which rejects unsupported `P` at compile time instead of requiring [SupportedPrime].
+impl<const P: u64> Prime<P> {
+    /// `(EXP, DIFF, bits_l, mask_l)`, where `P = 2^EXP - DIFF` and the lower half of operands has `bits_l` bits.
+    ///
+    /// Evaluation fails if `P` violates the constraints of `mul_mod`.
+    const MUL_MOD_PARAMS: (u64, u64, u64, u64) = {
+        // P = 2^EXP - DIFF
+        //
+        // # Constraints
+        //
+        // - EXP <= 61
+        // - (1 <=) DIFF <= 2^min(64-EXP, floor(EXP/2))
+        let exp = P.next_power_of_two().ilog2() as u64;
+        assert!(exp <= 61, "unsupported prime: P should be less than 2^61");
+
+        let diff = (1 << exp) - P;
+        assert!(
+            diff >= 1,
+            "unsupported prime: P should not be a power of two"
+        );
+        let max_diff_exp = if 64 - exp < exp / 2 {
+            64 - exp
+        } else {
+            exp / 2
+        };
+        assert!(
+            diff <= 1 << max_diff_exp,
+            "unsupported prime: P = 2^EXP - DIFF should satisfy DIFF <= 2^min(64-EXP, floor(EXP/2))"
+        );
+
+        // u: ⎿ EXP / 2 ⏌
+        // l: ⎾ EXP / 2 ⏋
+        let bits_l = exp.div_ceil(2);
+        let mask_l = (1 << bits_l) - 1;
+
+        (exp, diff, bits_l, mask_l)
+    };
+
+    /// Performs `(lhs * rhs) % P` without overflow.
+    ///
+    /// # Constraints
+    ///
+    /// - `lhs, rhs < P`. Otherwise, overflow may or may not occur.
+    /// - `P = 2^EXP - DIFF` should satisfy `EXP <= 61` and `DIFF <= 2^min(64-EXP, floor(EXP/2))`,
+    ///   which is checked at compile time. All of [`PRIMES`] satisfy this.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rolling_hash::Prime;
+    ///
+    /// type M61 = Prime<{ (1 << 61) - 1 }>;
+    /// // 2^60 * 4 = 2^62 = 2 * 2^61, and 2^61 is 1 modulo 2^61 - 1
+    /// assert_eq!(M61::mul_mod(1 << 60, 4), 2);
+    /// ```
+    pub const fn mul_mod(lhs: u64, rhs: u64) -> u64 {
+        debug_assert!(lhs < P && rhs < P, "operands should be less than P");
+
+        mul_mod_with(lhs, rhs, P, Self::MUL_MOD_PARAMS)
+    }
+}
+
+/// Modular arithmetic for supported prime numbers.
+///
 /// **FIXME**: These methods should be a [SupportedPrime]`s ones.
 impl<const P: u64> Prime<P>
 where
     Prime<P>: SupportedPrime,
 {
-    /// Performs `lhs + rhs % P` without overflow.
+    /// Performs `(lhs + rhs) % P` without overflow.
     ///
     /// # Constraints
     ///
-    /// - `lhs, rhs < P`. Otherwise, overflow may or may not occur.
+    /// - `lhs, rhs < P`. Otherwise, the result may not be reduced.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rolling_hash::Prime;
+    ///
+    /// type M61 = Prime<{ (1 << 61) - 1 }>;
+    /// assert_eq!(M61::add_mod((1 << 61) - 2, 3), 2);
+    /// ```
+    #[inline]
+    pub const fn add_mod(lhs: u64, rhs: u64) -> u64 {
+        debug_assert!(lhs < P && rhs < P, "operands should be less than P");
+
+        // lhs + rhs < 2P < 2^62
+        let sum = lhs + rhs;
+        if sum >= P { sum - P } else { sum }
+    }
+
+    /// Performs `(lhs - rhs) % P` without overflow, where the result is in `0..P`.
+    ///
+    /// # Constraints
+    ///
+    /// - `lhs, rhs < P`. Otherwise, underflow may or may not occur.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rolling_hash::Prime;
+    ///
+    /// type M61 = Prime<{ (1 << 61) - 1 }>;
+    /// assert_eq!(M61::sub_mod(2, 3), (1 << 61) - 2);
+    /// ```
+    #[inline]
+    pub const fn sub_mod(lhs: u64, rhs: u64) -> u64 {
+        debug_assert!(lhs < P && rhs < P, "operands should be less than P");
+
+        // lhs + P - rhs < 2P < 2^62
+        if lhs >= rhs { lhs - rhs } else { lhs + P - rhs }
+    }
+
+    /// Performs `value^exp % P` without overflow.
+    ///
+    /// # Constraints
+    ///
+    /// - `value < P`. Otherwise, overflow may or may not occur.
     /// - `P` is limited. See [SupportedPrime].
     ///
+    /// Any `exp` is accepted, since the loop runs at most 64 times.
+    /// For example, `pow_mod(value, P - 2)` is the inverse of non-zero `value` by Fermat's little theorem.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log *exp*)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rolling_hash::Prime;
+    ///
+    /// type M61 = Prime<{ (1 << 61) - 1 }>;
+    /// assert_eq!(M61::pow_mod(2, 61), 1);
+    /// assert_eq!(M61::pow_mod(3, 0), 1);
+    ///
+    /// // the inverse by Fermat's little theorem
+    /// let inverse = M61::pow_mod(3, (1 << 61) - 3);
+    /// assert_eq!(M61::mul_mod(3, inverse), 1);
+    /// ```
+    pub const fn pow_mod(mut value: u64, mut exp: u64) -> u64 {
+        let mut result = 1; // P >> 1
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = Self::mul_mod(result, value);
+            }
+            exp >>= 1;
+            value = Self::mul_mod(value, value);
+        }
+        result
+    }
+
+    /// Returns `true` if the multiplicative order of `base` divides `k`, that is, `base^k % P == 1`.
+    ///
+    /// # Constraints
+    ///
+    /// See [pow_mod](Self::pow_mod).
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(log *k*)
+    pub const fn order_divides(base: u64, k: u64) -> bool {
+        Self::pow_mod(base, k) == 1
+    }
+
+    /// Returns `false` if `base` is trivially bad, that is, `0`, `1`, `P - 1` or out of `0..P`.
+    ///
+    /// Powers of these bases are constant or periodic with period 2, so many sequences collide.
+    ///
     /// # Time complexity
     ///
     /// *O*(1)
-    pub(crate) const fn mul_mod(lhs: u64, rhs: u64) -> u64 {
-        let (exp, diff, bits_l, mask_l) = const {
-            // P = 2^EXP - DIFF
-            //
-            // # Constraints
-            //
-            // - EXP <= 61
-            // - (1 <=) DIFF <= 2^min(64-EXP, floor(EXP/2))
-            let exp = P.next_power_of_two().ilog2() as u64;
-            let diff = (1 << exp) - P;
-
-            // u: ⎿ EXP / 2 ⏌
-            // l: ⎾ EXP / 2 ⏋
-            let bits_l = (exp + 1) / 2;
-            let mask_l = (1 << bits_l) - 1;
-
-            (exp, diff, bits_l, mask_l)
-        };
+    pub const fn is_probably_good_base(base: u64) -> bool {
+        2 <= base && base <= P - 2
+    }
+}
 
-        let (lhs_l, lhs_u) = (lhs & mask_l, lhs >> bits_l);
-        let (rhs_l, rhs_u) = (rhs & mask_l, rhs >> bits_l);
-        // ==================================================
+/// Body of [`Prime::mul_mod`] with `P = p` and `(EXP, DIFF, bits_l, mask_l) = params`.
+///
+/// `params` is not checked here, so that overflow with an unsupported prime can be tested.
+const fn mul_mod_with(lhs: u64, rhs: u64, p: u64, params: (u64, u64, u64, u64)) -> u64 {
+    let (exp, diff, bits_l, mask_l) = params;
 
-        // lhs_u * rhs_u * 2^(2l) % (2^EXP - DIFF)
-        //
-        // (a) EXP is even (l = u)
-        // = lhs_u * rhs_u * 2^EXP % (2^EXP - DIFF)
-        // = lhs_u * rhs_u * DIFF % (2^EXP - DIFF)
-        //
-        // lhs_u * rhs_u < 2^(2u) = 2^EXP
-        //
-        // (b) EXP i odd (l = u + 1)
-        // = lhs_u * rhs_u * 2^(EXP+1) % (2^EXP - DIFF)
-        // = lhs_u * rhs_u * 2 * DIFF % (2^EXP - DIFF)
-        //
-        // lhs_u * rhs_u < 2^(2u) = 2^(EXP-1)
-        //
-        // # Constraints
-        //
-        // 2^EXP * DIFF < 2^64
-        let uu = lhs_u * rhs_u * (exp % 2 + 1) * diff;
+    let (lhs_l, lhs_u) = (lhs & mask_l, lhs >> bits_l);
+    let (rhs_l, rhs_u) = (rhs & mask_l, rhs >> bits_l);
+    // ==================================================
 
-        // ( lhs_u * rhs_l + lhs_l * rhs_u ) * 2^l % (2^EXP - DIFF)
-        // = ( cross_u * 2^(2l) + cross_l * 2^l ) % (2^EXP - DIFF)
-        //
-        // (a) EXP is even (l = u)
-        // = ( cross_u * 2^EXP + cross_l * 2^l ) % (2^EXP - DIFF)
-        // = ( cross_u * DIFF + cross_l * 2^l ) % (2^EXP - DIFF)
-        //
-        // cross_u * DIFF + cross_l * 2^l )
-        // < ( 2^(u+1) * DIFF + 2^(EXP+1) )
-        // = ( 2^(l+1) * DIFF + 2^(EXP+1) )
-        //
-        // (b) EXP is odd (l = u + 1)
-        // = ( cross_u * 2^(EXP+1) + cross_l * 2^l ) % (2^EXP - DIFF)
-        // = ( cross_u * 2 * DIFF + cross_l * 2^l ) % (2^EXP - DIFF)
-        //
-        // cross_u * 2 * DIFF + cross_l * 2^l
-        // < 2^(u+2) * DIFF + 2^(EXP+1)
-        // = 2^(l+1) * DIFF + 2^(EXP+1)
-        //
-        // # Constraints
-        //
-        // - DIFF <= 2^u
-        // - EXP <= 62
-        let cross = {
-            let cross = lhs_u * rhs_l + lhs_l * rhs_u;
-            let (cross_l, cross_u) = (cross & mask_l, cross >> bits_l);
-            cross_u * (exp % 2 + 1) * diff + (cross_l << bits_l)
-        };
+    // lhs_u * rhs_u * 2^(2l) % (2^EXP - DIFF)
+    //
+    // (a) EXP is even (l = u)
+    // = lhs_u * rhs_u * 2^EXP % (2^EXP - DIFF)
+    // = lhs_u * rhs_u * DIFF % (2^EXP - DIFF)
+    //
+    // lhs_u * rhs_u < 2^(2u) = 2^EXP
+    //
+    // (b) EXP i odd (l = u + 1)
+    // = lhs_u * rhs_u * 2^(EXP+1) % (2^EXP - DIFF)
+    // = lhs_u * rhs_u * 2 * DIFF % (2^EXP - DIFF)
+    //
+    // lhs_u * rhs_u < 2^(2u) = 2^(EXP-1)
+    //
+    // # Constraints
+    //
+    // 2^EXP * DIFF < 2^64
+    let uu = checked_mul(checked_mul(checked_mul(lhs_u, rhs_u), exp % 2 + 1), diff);
 
-        // lhs_l * rhs_l < 2^(2l)
-        //
-        // # Constraints
-        //
-        // EXP <= 64
-        let ll = lhs_l * rhs_l;
+    // ( lhs_u * rhs_l + lhs_l * rhs_u ) * 2^l % (2^EXP - DIFF)
+    // = ( cross_u * 2^(2l) + cross_l * 2^l ) % (2^EXP - DIFF)
+    //
+    // (a) EXP is even (l = u)
+    // = ( cross_u * 2^EXP + cross_l * 2^l ) % (2^EXP - DIFF)
+    // = ( cross_u * DIFF + cross_l * 2^l ) % (2^EXP - DIFF)
+    //
+    // cross_u * DIFF + cross_l * 2^l )
+    // < ( 2^(u+1) * DIFF + 2^(EXP+1) )
+    // = ( 2^(l+1) * DIFF + 2^(EXP+1) )
+    //
+    // (b) EXP is odd (l = u + 1)
+    // = ( cross_u * 2^(EXP+1) + cross_l * 2^l ) % (2^EXP - DIFF)
+    // = ( cross_u * 2 * DIFF + cross_l * 2^l ) % (2^EXP - DIFF)
+    //
+    // cross_u * 2 * DIFF + cross_l * 2^l
+    // < 2^(u+2) * DIFF + 2^(EXP+1)
+    // = 2^(l+1) * DIFF + 2^(EXP+1)
+    //
+    // # Constraints
+    //
+    // - DIFF <= 2^u
+    // - EXP <= 62
+    let cross = {
+        let cross = checked_add(checked_mul(lhs_u, rhs_l), checked_mul(lhs_l, rhs_u));
+        let (cross_l, cross_u) = (cross & mask_l, cross >> bits_l);
+        checked_add(
+            checked_mul(checked_mul(cross_u, exp % 2 + 1), diff),
+            cross_l << bits_l,
+        )
+    };
 
-        // # Constraints
-        //
-        // - (1 <=) DIFF <= min(64-EXP, floor(EXP/2))
-        // - EXP <= 62. Since the largest possible prime is 2^61 - 1, then EXP <= 61
-        //
-        // uu + cross + ll
-        // < (2^EXP * DIFF) + 2^(EXP+2) + 2^(EXP+EXP%2)
-        // < 2^64 + 2^63 + 2^62
-        (uu % P + cross + ll) % P
+    // lhs_l * rhs_l < 2^(2l)
+    //
+    // # Constraints
+    //
+    // EXP <= 64
+    let ll = checked_mul(lhs_l, rhs_l);
+
+    // # Constraints
+    //
+    // - (1 <=) DIFF <= min(64-EXP, floor(EXP/2))
+    // - EXP <= 62. Since the largest possible prime is 2^61 - 1, then EXP <= 61
+    //
+    // uu + cross + ll
+    // < (2^EXP * DIFF) + 2^(EXP+2) + 2^(EXP+EXP%2)
+    // < 2^64 + 2^63 + 2^62
+    checked_add(checked_add(uu % p, cross), ll) % p
+}
+
+/// Same as `lhs * rhs`, but panics on overflow if the `debug_checked` feature is enabled.
+///
+/// The message does not include `lhs` and `rhs`, since integers cannot be formatted in `const fn`.
+#[inline(always)]
+const fn checked_mul(lhs: u64, rhs: u64) -> u64 {
+    #[cfg(feature = "debug_checked")]
+    if lhs.checked_mul(rhs).is_none() {
+        panic!("overflow in mul_mod: intermediate product exceeds u64; is P supported?");
     }
+    lhs * rhs
+}
 
-    /// Performs `value^exp % P` without overflow.
+/// Same as `lhs + rhs`, but panics on overflow if the `debug_checked` feature is enabled.
+///
+/// The message does not include `lhs` and `rhs`, since integers cannot be formatted in `const fn`.
+#[inline(always)]
+const fn checked_add(lhs: u64, rhs: u64) -> u64 {
+    #[cfg(feature = "debug_checked")]
+    if lhs.checked_add(rhs).is_none() {
+        panic!("overflow in mul_mod: intermediate sum exceeds u64; is P supported?");
+    }
+    lhs + rhs
+}
+
+/// Modular arithmetic on lanes of prefix hashes, so that hashing code is generic over the lane type.
+///
+/// [`Prime`] has `u64` lanes, and [`PrimeU32`] has `u32` lanes.
+pub trait ModArith {
+    /// The type of residues modulo [`MODULUS`](Self::MODULUS).
+    type Lane: Copy + Eq + fmt::Debug + Into<u64>;
+
+    /// The prime number.
+    const MODULUS: u64;
+
+    /// `0` as a lane.
+    const ZERO: Self::Lane;
+
+    /// `1` as a lane.
+    const ONE: Self::Lane;
+
+    /// Converts a residue into a lane.
     ///
     /// # Constraints
     ///
-    /// See [mul_mod](Self::mul_mod).
+    /// - `value < MODULUS`.
+    fn from_residue(value: u64) -> Self::Lane;
+
+    /// Performs `(lhs + rhs) % MODULUS`, where `lhs, rhs < MODULUS`.
+    fn add_mod(lhs: Self::Lane, rhs: Self::Lane) -> Self::Lane;
+
+    /// Performs `(lhs - rhs) % MODULUS`, where `lhs, rhs < MODULUS`, and the result is in `0..MODULUS`.
+    fn sub_mod(lhs: Self::Lane, rhs: Self::Lane) -> Self::Lane;
+
+    /// Performs `(lhs * rhs) % MODULUS`, where `lhs, rhs < MODULUS`.
+    fn mul_mod(lhs: Self::Lane, rhs: Self::Lane) -> Self::Lane;
+
+    /// Performs `value^exp % MODULUS`, where `value < MODULUS`.
     ///
     /// # Time complexity
     ///
     /// *O*(log *exp*)
-    pub(crate) const fn pow_mod(mut value: u64, mut exp: u64) -> u64 {
-        let mut result = 1; // P >> 1
+    fn pow_mod(mut value: Self::Lane, mut exp: u64) -> Self::Lane {
+        let mut result = Self::ONE;
         while exp > 0 {
             if exp & 1 == 1 {
                 result = Self::mul_mod(result, value);
@@ -170,3 +390,319 @@ where
         result
     }
 }
+
+/// Delegates to the `const` methods.
+impl<const P: u64> ModArith for Prime<P>
+where
+    Prime<P>: SupportedPrime,
+{
+    type Lane = u64;
+
+    const MODULUS: u64 = P;
+    const ZERO: u64 = 0;
+    const ONE: u64 = 1;
+
+    #[inline]
+    fn from_residue(value: u64) -> u64 {
+        debug_assert!(value < P, "value should be less than P");
+        value
+    }
+
+    #[inline]
+    fn add_mod(lhs: u64, rhs: u64) -> u64 {
+        Prime::<P>::add_mod(lhs, rhs)
+    }
+
+    #[inline]
+    fn sub_mod(lhs: u64, rhs: u64) -> u64 {
+        Prime::<P>::sub_mod(lhs, rhs)
+    }
+
+    #[inline]
+    fn mul_mod(lhs: u64, rhs: u64) -> u64 {
+        Prime::<P>::mul_mod(lhs, rhs)
+    }
+
+    #[inline]
+    fn pow_mod(value: u64, exp: u64) -> u64 {
+        Prime::<P>::pow_mod(value, exp)
+    }
+}
+
+/// Specified 32-bit prime number that is suitable for [`OneWayU32`](crate::OneWayU32).
+#[derive(Debug, Clone, Copy)]
+pub struct PrimeU32<const P: u64>;
+
+/// A marker trait for 32-bit prime numbers that are suitable for [`OneWayU32`](crate::OneWayU32).
+pub trait SupportedPrimeU32 {}
+
+macro_rules! supported_prime_u32_impl {
+    ($n:literal; $( (1 << $exp:literal) - $diff:literal),*$(,)?) => {
+        /// Large 32-bit prime numbers that is suitable for [`OneWayU32`](crate::OneWayU32).
+        pub const PRIMES_U32: [u64; $n] = [$( { (1 << $exp) - $diff } ),*];
+
+        $(
+            impl SupportedPrimeU32 for PrimeU32<{ (1 << $exp) - $diff }> {}
+        )*
+    };
+}
+
+supported_prime_u32_impl! {
+    6;
+    (1 << 32) - 5,
+    (1 << 32) - 17,
+    (1 << 32) - 65,
+    (1 << 31) - 1,
+    (1 << 31) - 19,
+    (1 << 31) - 61,
+}
+
+/// Modular arithmetic for supported 32-bit prime numbers.
+///
+/// Unlike [`Prime`], intermediates simply fit in `u64`.
+impl<const P: u64> ModArith for PrimeU32<P>
+where
+    PrimeU32<P>: SupportedPrimeU32,
+{
+    type Lane = u32;
+
+    const MODULUS: u64 = P;
+    const ZERO: u32 = 0;
+    const ONE: u32 = 1;
+
+    #[inline]
+    fn from_residue(value: u64) -> u32 {
+        debug_assert!(value < P, "value should be less than P");
+        value as u32
+    }
+
+    #[inline]
+    fn add_mod(lhs: u32, rhs: u32) -> u32 {
+        debug_assert!(
+            (lhs as u64) < P && (rhs as u64) < P,
+            "operands should be less than P"
+        );
+
+        // lhs + rhs < 2P < 2^33
+        let sum = lhs as u64 + rhs as u64;
+        if sum >= P {
+            (sum - P) as u32
+        } else {
+            sum as u32
+        }
+    }
+
+    #[inline]
+    fn sub_mod(lhs: u32, rhs: u32) -> u32 {
+        debug_assert!(
+            (lhs as u64) < P && (rhs as u64) < P,
+            "operands should be less than P"
+        );
+
+        if lhs >= rhs {
+            lhs - rhs
+        } else {
+            (lhs as u64 + P - rhs as u64) as u32
+        }
+    }
+
+    #[inline]
+    fn mul_mod(lhs: u32, rhs: u32) -> u32 {
+        debug_assert!(
+            (lhs as u64) < P && (rhs as u64) < P,
+            "operands should be less than P"
+        );
+
+        // lhs * rhs < 2^64
+        (lhs as u64 * rhs as u64 % P) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    /// Calls `check::<P>()` for every `P` in [`PRIMES`].
+    macro_rules! for_each_prime {
+        ($check:ident) => {
+            $check::<{ PRIMES[0] }>();
+            $check::<{ PRIMES[1] }>();
+            $check::<{ PRIMES[2] }>();
+            $check::<{ PRIMES[3] }>();
+            $check::<{ PRIMES[4] }>();
+            $check::<{ PRIMES[5] }>();
+            $check::<{ PRIMES[6] }>();
+            $check::<{ PRIMES[7] }>();
+            $check::<{ PRIMES[8] }>();
+            $check::<{ PRIMES[9] }>();
+        };
+    }
+
+    /// Calls `check::<P>()` for every `P` in [`PRIMES_U32`].
+    macro_rules! for_each_prime_u32 {
+        ($check:ident) => {
+            $check::<{ PRIMES_U32[0] }>();
+            $check::<{ PRIMES_U32[1] }>();
+            $check::<{ PRIMES_U32[2] }>();
+            $check::<{ PRIMES_U32[3] }>();
+            $check::<{ PRIMES_U32[4] }>();
+            $check::<{ PRIMES_U32[5] }>();
+        };
+    }
+
+    /// Compares the arithmetic of `M` with `u128` arithmetic, for boundary and random residues.
+    fn check_mod_arith<M: ModArith>() {
+        let p = M::MODULUS;
+        let mut rng = StdRng::seed_from_u64(p);
+        let mut residues = vec![0, 1, 2, p / 2, p - 2, p - 1];
+        residues.extend((0..64).map(|_| rng.random_range(0..p)));
+
+        let wide_p = p as u128;
+        for &lhs in &residues {
+            for &rhs in &residues {
+                let (a, b) = (M::from_residue(lhs), M::from_residue(rhs));
+                let (wide_lhs, wide_rhs) = (lhs as u128, rhs as u128);
+                assert_eq!(
+                    M::add_mod(a, b).into() as u128,
+                    (wide_lhs + wide_rhs) % wide_p
+                );
+                assert_eq!(
+                    M::sub_mod(a, b).into() as u128,
+                    (wide_lhs + wide_p - wide_rhs) % wide_p
+                );
+                assert_eq!(
+                    M::mul_mod(a, b).into() as u128,
+                    wide_lhs * wide_rhs % wide_p
+                );
+            }
+
+            let mut pow = 1u128;
+            for exp in 0..8 {
+                assert_eq!(M::pow_mod(M::from_residue(lhs), exp).into() as u128, pow);
+                pow = pow * lhs as u128 % wide_p;
+            }
+            if lhs != 0 {
+                assert_eq!(M::pow_mod(M::from_residue(lhs), p - 1), M::ONE);
+            }
+        }
+    }
+
+    #[test]
+    fn sub_mod_wraps_around() {
+        fn check<const P: u64>()
+        where
+            Prime<P>: SupportedPrime,
+        {
+            assert_eq!(Prime::<P>::sub_mod(0, 1), P - 1);
+            assert_eq!(Prime::<P>::sub_mod(2, 5), P - 3);
+            assert_eq!(Prime::<P>::sub_mod(0, P - 1), 1);
+            assert_eq!(Prime::<P>::sub_mod(P - 1, P - 1), 0);
+            assert_eq!(Prime::<P>::sub_mod(5, 2), 3);
+
+            for (lhs, rhs) in [(0, P - 1), (1, P - 2), (P / 2, P - 1), (3, 7)] {
+                let diff = Prime::<P>::sub_mod(lhs, rhs);
+                assert!(diff < P);
+                assert_eq!(Prime::<P>::add_mod(diff, rhs), lhs);
+            }
+        }
+        for_each_prime!(check);
+    }
+
+    #[test]
+    fn add_mod_wraps_around() {
+        fn check<const P: u64>()
+        where
+            Prime<P>: SupportedPrime,
+        {
+            assert_eq!(Prime::<P>::add_mod(P - 1, 1), 0);
+            assert_eq!(Prime::<P>::add_mod(P - 1, P - 1), P - 2);
+            assert_eq!(Prime::<P>::add_mod(2, 3), 5);
+        }
+        for_each_prime!(check);
+    }
+
+    #[test]
+    fn arithmetic_does_not_overflow_at_boundaries() {
+        fn check<const P: u64>()
+        where
+            Prime<P>: SupportedPrime,
+        {
+            check_mod_arith::<Prime<P>>();
+        }
+        fn check_u32<const P: u64>()
+        where
+            PrimeU32<P>: SupportedPrimeU32,
+        {
+            check_mod_arith::<PrimeU32<P>>();
+        }
+        for_each_prime!(check);
+        for_each_prime_u32!(check_u32);
+    }
+
+    #[test]
+    fn order_divides_known_orders() {
+        type M61 = Prime<{ (1 << 61) - 1 }>;
+        // 2^61 = 1, and 61 is a prime, so the order of 2 is exactly 61
+        assert!(M61::order_divides(2, 61));
+        assert!(M61::order_divides(2, 122));
+        assert!(!M61::order_divides(2, 60));
+        assert!(!M61::order_divides(2, 1));
+
+        fn check<const P: u64>()
+        where
+            Prime<P>: SupportedPrime,
+        {
+            // -1 has order 2, and 1 has order 1
+            assert!(Prime::<P>::order_divides(P - 1, 2));
+            assert!(!Prime::<P>::order_divides(P - 1, 1));
+            assert!(Prime::<P>::order_divides(1, 1));
+
+            for base in [0, 1, P - 1, P] {
+                assert!(!Prime::<P>::is_probably_good_base(base));
+            }
+            for base in [2, 3, P / 2, P - 2] {
+                assert!(Prime::<P>::is_probably_good_base(base));
+            }
+        }
+        for_each_prime!(check);
+    }
+
+    #[cfg(feature = "debug_checked")]
+    #[test]
+    #[should_panic(expected = "overflow in mul_mod")]
+    fn debug_checked_panics_for_unsupported_prime() {
+        // 2^63 - 25 is a prime, but 2^EXP * DIFF exceeds 2^64
+        let p = (1 << 63) - 25;
+        let bits_l = 32;
+        let _ = mul_mod_with(p - 1, p - 1, p, (63, 25, bits_l, (1 << bits_l) - 1));
+    }
+
+    #[test]
+    fn fermat_holds_for_every_prime() {
+        fn check<const P: u64>()
+        where
+            Prime<P>: SupportedPrime,
+        {
+            let mut rng = StdRng::seed_from_u64(P);
+            let mut bases = vec![1, 2, 3, P / 2, P - 2, P - 1];
+            bases.extend((0..8).map(|_| rng.random_range(1..P)));
+            for base in bases {
+                assert_eq!(
+                    Prime::<P>::pow_mod(base, P - 1),
+                    1,
+                    "P = {P}, base = {base}"
+                );
+                assert_eq!(Prime::<P>::pow_mod(base, P), base, "P = {P}, base = {base}");
+            }
+            assert_eq!(Prime::<P>::pow_mod(0, P - 1), 0);
+            // `u64::MAX = (P - 1) * q + r`, so `2^u64::MAX = 2^r`
+            assert_eq!(
+                Prime::<P>::pow_mod(2, u64::MAX),
+                Prime::<P>::pow_mod(2, u64::MAX % (P - 1))
+            );
+        }
+        for_each_prime!(check);
+    }
+}
@@ -0,0 +1,129 @@
+//! Property tests comparing searches with a naive search, across a couple of [`PRIMES`].
+use proptest::prelude::*;
+
+use crate::{BaseCount, OneWay, PRIMES, Prime, SupportedBaseCount, SupportedPrime};
+
+const SMALL: u64 = PRIMES[0];
+const LARGE: u64 = PRIMES[PRIMES.len() - 1];
+
+/// Returns all indexes of `needle` in `haystack` by comparing elements directly.
+fn naive_positions(haystack: &[u64], needle: &[u64]) -> Vec<usize> {
+    if needle.is_empty() {
+        return (0..=haystack.len()).collect();
+    }
+    haystack
+        .windows(needle.len())
+        .enumerate()
+        .filter_map(|(i, window)| (window == needle).then_some(i))
+        .collect()
+}
+
+fn check_search<const P: u64, const B: usize>(haystack: &[u64], needle: &[u64], bases: [u64; B])
+where
+    Prime<P>: SupportedPrime,
+    BaseCount<B>: SupportedBaseCount,
+{
+    let hasher = OneWay::<P, B>::from_slice_with_bases(haystack, bases);
+    // elements are compared modulo `P`
+    let haystack: Vec<_> = haystack.iter().map(|value| value % P).collect();
+    let needle: Vec<_> = needle.iter().map(|value| value % P).collect();
+    let expected = naive_positions(&haystack, &needle);
+
+    let position = hasher.position(&needle).map(|i| *i);
+    if let Some(i) = position {
+        assert_eq!(haystack[i..i + needle.len()], needle, "collision at {i}");
+    }
+    assert_eq!(position, expected.first().copied());
+    assert_eq!(
+        hasher.rposition(&needle).map(|i| *i),
+        expected.last().copied()
+    );
+    assert_eq!(
+        hasher.positions(&needle).map(|i| *i).collect::<Vec<_>>(),
+        expected
+    );
+    assert_eq!(*hasher.count(&needle), expected.len());
+}
+
+fn check_windows<const P: u64, const B: usize>(
+    haystack: &[u64],
+    size: usize,
+    from_back: &[bool],
+    bases: [u64; B],
+) where
+    Prime<P>: SupportedPrime,
+    BaseCount<B>: SupportedBaseCount,
+{
+    let hasher = OneWay::<P, B>::from_slice_with_bases(haystack, bases);
+    let expected: Vec<_> = (0..(haystack.len() + 1).saturating_sub(size))
+        .map(|i| hasher.hash_of(&haystack[i..i + size]))
+        .collect();
+
+    assert_eq!(hasher.windows(size).collect::<Vec<_>>(), expected);
+    let mut backward: Vec<_> = hasher.windows(size).rev().collect();
+    backward.reverse();
+    assert_eq!(backward, expected);
+
+    // pull from either end, in the order given by `from_back`
+    let mut windows = hasher.windows(size);
+    let (mut front, mut back) = (0, expected.len());
+    for &from_back in from_back {
+        assert_eq!(windows.len(), back - front);
+        let item = if from_back {
+            windows.next_back()
+        } else {
+            windows.next()
+        };
+        if front == back {
+            assert_eq!(item, None);
+        } else if from_back {
+            back -= 1;
+            assert_eq!(item, Some(expected[back]));
+        } else {
+            front += 1;
+            assert_eq!(item, Some(expected[front - 1]));
+        }
+    }
+}
+
+fn bases<const P: u64>() -> impl Strategy<Value = [u64; 2]> {
+    [2..=P - 2, 2..=P - 2]
+}
+
+proptest! {
+    #[test]
+    fn search_agrees_with_naive(
+        haystack in prop::collection::vec(0..4u64, 0..64),
+        needle in prop::collection::vec(0..4u64, 0..6),
+        small_bases in bases::<SMALL>(),
+        large_bases in bases::<LARGE>(),
+    ) {
+        check_search::<SMALL, 2>(&haystack, &needle, small_bases);
+        check_search::<LARGE, 2>(&haystack, &needle, large_bases);
+    }
+
+    #[test]
+    fn search_for_sub_slice_agrees_with_naive(
+        haystack in prop::collection::vec(any::<u64>(), 1..64),
+        range in (0..64usize, 0..8usize),
+        small_bases in bases::<SMALL>(),
+        large_bases in bases::<LARGE>(),
+    ) {
+        let start = range.0 % haystack.len();
+        let needle = &haystack[start..(start + range.1).min(haystack.len())];
+        check_search::<SMALL, 2>(&haystack, needle, small_bases);
+        check_search::<LARGE, 2>(&haystack, needle, large_bases);
+    }
+
+    #[test]
+    fn windows_forward_and_backward_agree(
+        haystack in prop::collection::vec(any::<u64>(), 0..48),
+        size in 1..8usize,
+        from_back in prop::collection::vec(any::<bool>(), 0..64),
+        small_bases in bases::<SMALL>(),
+        large_bases in bases::<LARGE>(),
+    ) {
+        check_windows::<SMALL, 2>(&haystack, size, &from_back, small_bases);
+        check_windows::<LARGE, 2>(&haystack, size, &from_back, large_bases);
+    }
+}
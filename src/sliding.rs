@@ -0,0 +1,202 @@
+use std::collections::VecDeque;
+
+use crate::{BaseCount, OneWay, Prime, SupportedBaseCount, SupportedPrime};
+
+/// A rolling hash of the last `window` elements, which keeps them in a ring buffer.
+///
+/// Unlike [`OneWay`], prefix hashes are not kept, so only *O*(*window* + *B*) memory is used.
+/// This is the primitive of content-defined chunking.
+#[derive(Debug, Clone)]
+pub struct SlidingWindow<const P: u64, const B: usize>
+where
+    Prime<P>: SupportedPrime,
+    BaseCount<B>: SupportedBaseCount,
+{
+    base: [u64; B],
+    /// `base^window`
+    base_pow_window: [u64; B],
+    /// elements in the window, reduced modulo `P`
+    buffer: VecDeque<u64>,
+    window: usize,
+    hash: [u64; B],
+}
+
+impl<const P: u64, const B: usize> SlidingWindow<P, B>
+where
+    Prime<P>: SupportedPrime,
+    BaseCount<B>: SupportedBaseCount,
+{
+    /// Creates a new instance with randomly generated bases.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` is `0`.
+    #[inline]
+    pub fn new(window: usize) -> Self {
+        Self::with_base(*OneWay::<P, B>::new().base(), window)
+    }
+
+    /// Creates a new instance with specified bases.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` is `0`. See also [`OneWay::with_base`].
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B* log *window*)
+    pub fn with_base(base: [u64; B], window: usize) -> Self {
+        assert!(window > 0, "window must not be empty");
+
+        let hasher = OneWay::<P, B>::with_base(base);
+        Self {
+            base: *hasher.base(),
+            base_pow_window: hasher.base_pow(window),
+            buffer: VecDeque::with_capacity(window),
+            window,
+            hash: [0; B],
+        }
+    }
+
+    /// Returns the bases.
+    pub fn base(&self) -> &[u64; B] {
+        &self.base
+    }
+
+    /// Returns the maximum number of elements in the window.
+    #[inline]
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Returns the number of elements in the window, which is at most [`window`](Self::window).
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns `true` if the window has no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// Returns `true` if the window has [`window`](Self::window) elements.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.buffer.len() == self.window
+    }
+
+    /// Appends an element to the window, removing the oldest one if the window is full.
+    ///
+    /// Returns the removed element modulo `P`, if any.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*)
+    pub fn push(&mut self, value: u64) -> Option<u64> {
+        let value = value % P;
+        let removed = if self.is_full() {
+            self.buffer.pop_front()
+        } else {
+            None
+        };
+        self.buffer.push_back(value);
+
+        self.hash = std::array::from_fn(|i| {
+            let hash = Prime::<P>::add_mod(Prime::<P>::mul_mod(self.hash[i], self.base[i]), value);
+            match removed {
+                Some(removed) => {
+                    Prime::<P>::sub_mod(hash, Prime::<P>::mul_mod(removed, self.base_pow_window[i]))
+                }
+                None => hash,
+            }
+        });
+
+        removed
+    }
+
+    /// Pushes all the elements of `data`, returning chunk boundaries in `data`.
+    ///
+    /// A boundary `i` is emitted right after pushing `data[i - 1]`, if the window is full and `hash()[0] & mask == 0`.
+    /// Since hashes are uniformly distributed, chunks are about `2^mask.count_ones()` elements long on average.
+    /// The window is cleared first, so the same `data` always gets the same boundaries for the same bases.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BM*), where *M* is `data.len()`.
+    pub fn chunk_boundaries(&mut self, data: &[u64], mask: u64) -> Vec<usize> {
+        self.buffer.clear();
+        self.hash = [0; B];
+
+        let mut boundaries = Vec::new();
+        for (i, &value) in data.iter().enumerate() {
+            self.push(value);
+            if self.is_full() && self.hash[0] & mask == 0 {
+                boundaries.push(i + 1);
+            }
+        }
+        boundaries
+    }
+
+    /// Returns the hash of the elements in the window, or `[0; B]` if it is empty.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*)
+    #[inline]
+    pub fn hash(&self) -> [u64; B] {
+        self.hash
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    const P: u64 = (1 << 61) - 1;
+    const BASES: [u64; 2] = [1_000_003, 998_244_353];
+
+    #[test]
+    fn hash_matches_hash_of_window() {
+        let oneway = OneWay::<P, 2>::with_base(BASES);
+        let mut sliding = SlidingWindow::<P, 2>::with_base(BASES, 3);
+        assert_eq!(sliding.hash(), [0; 2]);
+
+        let data = [3, 1, 4, P + 1, 5, 9, 2, 6];
+        for (i, &value) in data.iter().enumerate() {
+            let removed = sliding.push(value);
+            assert_eq!(removed, i.checked_sub(3).map(|j| data[j] % P));
+            let window = &data[i.saturating_sub(2)..=i];
+            assert_eq!(sliding.len(), window.len());
+            assert_eq!(sliding.hash(), oneway.hash_of(window));
+        }
+    }
+
+    #[test]
+    fn chunk_boundaries_recur_with_the_period() {
+        const PERIOD: usize = 16;
+        let mut rng = StdRng::seed_from_u64(378);
+        let pattern: Vec<u64> = (0..PERIOD).map(|_| rng.random()).collect();
+        let data = pattern.repeat(8);
+
+        let mut sliding = SlidingWindow::<P, 2>::with_base(BASES, 4);
+        let boundaries = sliding.chunk_boundaries(&data, 0b11);
+        assert!(!boundaries.is_empty());
+        // the window left by the previous call is cleared
+        assert_eq!(sliding.chunk_boundaries(&data, 0b11), boundaries);
+        sliding.push(rng.random());
+        assert_eq!(sliding.chunk_boundaries(&data, 0b11), boundaries);
+        // once the window is full, the hash depends only on the position in the pattern
+        for &boundary in &boundaries {
+            if boundary + PERIOD <= data.len() {
+                assert!(boundaries.contains(&(boundary + PERIOD)), "{boundaries:?}");
+            }
+            if boundary >= 4 + PERIOD {
+                assert!(boundaries.contains(&(boundary - PERIOD)), "{boundaries:?}");
+            }
+        }
+    }
+}
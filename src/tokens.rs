@@ -0,0 +1,103 @@
+use crate::{BaseCount, Maybe, OneWay, Prime, Reduce, SupportedBaseCount, SupportedPrime};
+
+/// A [`OneWay`] which also keeps the original tokens.
+///
+/// Distinct tokens may be reduced to the same residue, so matches of the hasher are verified against the tokens.
+#[derive(Clone)]
+pub struct TokenHasher<const P: u64, const B: usize, T>
+where
+    Prime<P>: SupportedPrime,
+    BaseCount<B>: SupportedBaseCount,
+{
+    hasher: OneWay<P, B>,
+    tokens: Vec<T>,
+}
+
+impl<const P: u64, const B: usize, T> TokenHasher<P, B, T>
+where
+    Prime<P>: SupportedPrime,
+    BaseCount<B>: SupportedBaseCount,
+    T: Reduce<P> + Eq,
+{
+    /// Creates a new instance from `tokens`, where each token is [reduced](Reduce).
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN*), where *N* is `tokens.len()`.
+    pub fn from_tokens(tokens: Vec<T>) -> Self {
+        Self {
+            hasher: OneWay::from_slice(&tokens),
+            tokens,
+        }
+    }
+
+    /// Returns the underlying hasher.
+    pub fn hasher(&self) -> &OneWay<P, B> {
+        &self.hasher
+    }
+
+    /// Returns the original tokens.
+    pub fn tokens(&self) -> &[T] {
+        &self.tokens
+    }
+
+    /// Returns `true` if `needle` occurs at `index`, comparing the tokens directly.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*M*), where *M* is `needle.len()`.
+    pub fn verify_at(&self, index: usize, needle: &[T]) -> bool {
+        self.tokens
+            .get(index..)
+            .is_some_and(|rest| rest.starts_with(needle))
+    }
+
+    /// Searches for `needle` in `self`, returning its index.
+    ///
+    /// Every candidate is verified, so the result is always [certain](Maybe::is_certain).
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*BN* + *KM*), where *N* is `self.tokens().len()`, *M* is `needle.len()`
+    /// and *K* is the number of candidates.
+    #[must_use]
+    pub fn position(&self, needle: &[T]) -> Option<Maybe<usize>> {
+        self.hasher
+            .positions(needle)
+            .find_map(|i| i.verify(|&i| self.verify_at(i, needle)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const P: u64 = (1 << 61) - 1;
+
+    /// A token whose residue is chosen by hand, to force collisions.
+    #[derive(Debug, PartialEq, Eq)]
+    struct Token(&'static str, u64);
+
+    impl Reduce<P> for Token {
+        fn reduce(&self) -> u64 {
+            self.1
+        }
+    }
+
+    #[test]
+    fn forced_collision_is_verified_away() {
+        let hasher =
+            TokenHasher::<P, 2, _>::from_tokens(vec![Token("x", 1), Token("y", 1), Token("z", 2)]);
+        let needle = [Token("y", 1)];
+        // the hasher cannot tell `x` from `y`
+        assert_eq!(hasher.hasher().position(&needle).map(|i| *i), Some(0));
+        assert!(!hasher.verify_at(0, &needle));
+        assert!(hasher.verify_at(1, &needle));
+
+        let found = hasher.position(&needle).unwrap();
+        assert_eq!(*found, 1);
+        assert!(found.is_certain());
+        assert!(hasher.position(&[Token("w", 1)]).is_none());
+        assert!(!hasher.verify_at(4, &needle));
+    }
+}
@@ -1,65 +1,107 @@
-use std::{cell::OnceCell, num::NonZero};
+use std::{cell::OnceCell, iter::StepBy, num::NonZero};
 
-use crate::{BaseCount, OneWay, Prime, SupportedBaseCount, SupportedPrime};
+use crate::{BaseCount, ModArith, Prime, SupportedBaseCount};
 
-pub(crate) struct Windows<'a, const P: u64, const B: usize>
+/// An iterator over hashes of overlapping sub slices of length `size`.
+///
+/// This `struct` is created by [`OneWay::windows`](crate::OneWay::windows) and [`OneWayU32::windows`](crate::OneWayU32::windows).
+/// Lanes of hashes are [`M::Lane`](ModArith::Lane), which is `u64` by default.
+#[derive(Clone, Debug)]
+pub struct Windows<'a, const P: u64, const B: usize, M = Prime<P>>
 where
-    Prime<P>: SupportedPrime,
     BaseCount<B>: SupportedBaseCount,
+    M: ModArith,
 {
-    hash: &'a [[u64; B]],
+    hash: &'a [[M::Lane; B]],
     size: NonZero<usize>,
 
-    base_or_offset: [u64; B],
-    base_pow_size: OnceCell<[u64; B]>,
+    base_or_offset: [M::Lane; B],
+    base_pow_size: OnceCell<[M::Lane; B]>,
 }
 
-impl<'a, const P: u64, const B: usize> Windows<'a, P, B>
+impl<'a, const P: u64, const B: usize, M> Windows<'a, P, B, M>
 where
-    Prime<P>: SupportedPrime,
     BaseCount<B>: SupportedBaseCount,
+    M: ModArith,
 {
-    pub(crate) fn new(hasher: &'a OneWay<P, B>, size: NonZero<usize>) -> Self {
+    /// Creates an iterator over windows of the prefix hashes `hash` with `base`.
+    pub(crate) fn new(hash: &'a [[M::Lane; B]], base: [M::Lane; B], size: NonZero<usize>) -> Self {
         Self {
-            hash: hasher.get_hash(),
+            hash,
             size,
-            base_or_offset: hasher.base().clone(),
+            base_or_offset: base,
             base_pow_size: OnceCell::new(),
         }
     }
+
+    /// Returns `base^size`, initializing it on the first call.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B* log *size*) for the first call, *O*(*B*) otherwise.
+    fn base_pow_size(&mut self) -> [M::Lane; B] {
+        *self.base_pow_size.get_or_init(|| {
+            let size = u64::try_from(self.size.get()).expect("window size should fit in u64");
+            let pow = std::array::from_fn(|i| M::pow_mod(self.base_or_offset[i], size));
+            // initialized only once
+            self.base_or_offset.fill(M::ZERO);
+            pow
+        })
+    }
+
+    /// Returns `true` if no windows remain.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(1)
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.size.get() > self.hash.len()
+    }
+
+    /// Creates an iterator yielding every `step`-th window, starting from the first one.
+    ///
+    /// Skipped windows are not computed at all.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is `0`.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*B*) per item.
+    #[inline]
+    pub fn sampled(self, step: usize) -> StepBy<Self> {
+        // `StepBy` is built on top of `nth`, which skips windows in O(B).
+        self.step_by(step)
+    }
 }
 
-impl<'a, const P: u64, const B: usize> ExactSizeIterator for Windows<'a, P, B>
+impl<'a, const P: u64, const B: usize, M> ExactSizeIterator for Windows<'a, P, B, M>
 where
-    Prime<P>: SupportedPrime,
     BaseCount<B>: SupportedBaseCount,
+    M: ModArith,
 {
 }
 
-impl<'a, const P: u64, const B: usize> Iterator for Windows<'a, P, B>
+impl<'a, const P: u64, const B: usize, M> Iterator for Windows<'a, P, B, M>
 where
-    Prime<P>: SupportedPrime,
     BaseCount<B>: SupportedBaseCount,
+    M: ModArith,
 {
-    type Item = [u64; B];
+    type Item = [M::Lane; B];
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.size.get() > self.hash.len() {
             None
         } else {
-            let base_pow_size = self.base_pow_size.get_or_init(|| {
-                let pow = std::array::from_fn(|i| {
-                    Prime::<P>::pow_mod(self.base_or_offset[i], self.size.get() as u64)
-                });
-                // initialized only once
-                self.base_or_offset.fill(0);
-                pow
-            });
+            let base_pow_size = self.base_pow_size();
 
             let ret = std::array::from_fn(|i| {
-                (self.hash[self.size.get() - 1][i] + P
-                    - Prime::<P>::mul_mod(self.base_or_offset[i], base_pow_size[i]))
-                    % P
+                M::sub_mod(
+                    self.hash[self.size.get() - 1][i],
+                    M::mul_mod(self.base_or_offset[i], base_pow_size[i]),
+                )
             });
 
             self.base_or_offset = self.hash[0];
@@ -69,36 +111,46 @@ where
         }
     }
 
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if n > 0 {
+            if n + self.size.get() > self.hash.len() {
+                self.hash = &self.hash[self.hash.len()..];
+                return None;
+            }
+
+            // `base_or_offset` should hold the offset before skipping.
+            self.base_pow_size();
+            self.base_or_offset = self.hash[n - 1];
+            self.hash = &self.hash[n..];
+        }
+
+        self.next()
+    }
+
     fn size_hint(&self) -> (usize, Option<usize>) {
         let size = self.hash.len().saturating_sub(self.size.get() - 1);
         (size, Some(size))
     }
 }
 
-impl<'a, const P: u64, const B: usize> DoubleEndedIterator for Windows<'a, P, B>
+impl<'a, const P: u64, const B: usize, M> DoubleEndedIterator for Windows<'a, P, B, M>
 where
-    Prime<P>: SupportedPrime,
     BaseCount<B>: SupportedBaseCount,
+    M: ModArith,
 {
     fn next_back(&mut self) -> Option<Self::Item> {
         match self.size.get().cmp(&self.hash.len()) {
             std::cmp::Ordering::Less => {
-                let base_pow_size = self.base_pow_size.get_or_init(|| {
-                    let pow = std::array::from_fn(|i| {
-                        Prime::<P>::pow_mod(self.base_or_offset[i], self.size.get() as u64)
-                    });
-                    // initialized only once
-                    self.base_or_offset.fill(0);
-                    pow
-                });
+                let base_pow_size = self.base_pow_size();
 
                 let ret = std::array::from_fn(|i| {
-                    (self.hash[self.hash.len() - 1][i] + P
-                        - Prime::<P>::mul_mod(
+                    M::sub_mod(
+                        self.hash[self.hash.len() - 1][i],
+                        M::mul_mod(
                             self.hash[self.hash.len() - self.size.get() - 1][i],
                             base_pow_size[i],
-                        ))
-                        % P
+                        ),
+                    )
                 });
 
                 self.hash = &self.hash[..self.hash.len() - 1];
@@ -106,11 +158,174 @@ where
                 Some(ret)
             }
             std::cmp::Ordering::Equal => {
-                let ret = self.hash[self.size.get() - 1];
+                // `base_or_offset` holds the offset only after initialization.
+                let base_pow_size = self.base_pow_size();
+
+                let ret = std::array::from_fn(|i| {
+                    M::sub_mod(
+                        self.hash[self.size.get() - 1][i],
+                        M::mul_mod(self.base_or_offset[i], base_pow_size[i]),
+                    )
+                });
+
                 self.hash = &self.hash[..self.size.get() - 1];
+
                 Some(ret)
             }
             std::cmp::Ordering::Greater => None,
         }
     }
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        if n > 0 {
+            if n + self.size.get() > self.hash.len() {
+                self.hash = &self.hash[..0];
+                return None;
+            }
+
+            self.hash = &self.hash[..self.hash.len() - n];
+        }
+
+        self.next_back()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::OneWay;
+
+    const P: u64 = (1 << 61) - 1;
+
+    fn build(slice: &[u64]) -> OneWay<P, 2> {
+        OneWay::from_slice_with_bases(slice, [1_000_003, 998_244_353])
+    }
+
+    #[test]
+    fn sampled_matches_step_by() {
+        let hasher = build(&[3, 1, 4, 1, 5, 9, 2, 6, 5, 3]);
+        for size in 1..=hasher.len() {
+            let all: Vec<_> = hasher.windows(size).collect();
+            for step in 1..4 {
+                let sampled: Vec<_> = hasher.windows(size).sampled(step).collect();
+                let expected: Vec<_> = all.iter().copied().step_by(step).collect();
+                assert_eq!(sampled, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn large_window_sizes() {
+        let slice: Vec<u64> = (0..1000).collect();
+        let hasher = build(&slice);
+        assert_eq!(
+            hasher.windows(1000).collect::<Vec<_>>(),
+            [hasher.fingerprint()]
+        );
+        assert_eq!(
+            hasher.windows(999).rev().collect::<Vec<_>>(),
+            [hasher.hash_of(&slice[1..]), hasher.hash_of(&slice[..999])]
+        );
+        assert_eq!(hasher.windows(1001).len(), 0);
+        assert_eq!(hasher.windows(usize::MAX).next(), None);
+        assert_eq!(hasher.windows(usize::MAX).next_back(), None);
+    }
+
+    #[test]
+    fn clones_are_independent() {
+        let hasher = build(&[3, 1, 4, 1, 5, 9, 2, 6, 5, 3]);
+        let mut windows = hasher.windows(3);
+        // `base^size` is not initialized yet
+        let fresh = windows.clone();
+        windows.next();
+        windows.next_back();
+        let advanced = windows.clone();
+
+        assert!(format!("{windows:?}").starts_with("Windows"));
+        let remaining: Vec<_> = windows.collect();
+        assert_eq!(advanced.collect::<Vec<_>>(), remaining);
+        assert_eq!(fresh.len(), 8);
+        assert_eq!(fresh.skip(1).take(6).collect::<Vec<_>>(), remaining);
+    }
+
+    #[test]
+    fn len_is_exact_after_mixed_calls() {
+        #[derive(Clone, Copy, Debug)]
+        enum Call {
+            Next,
+            NextBack,
+            Nth(usize),
+            NthBack(usize),
+        }
+        let calls = [
+            Call::Next,
+            Call::NextBack,
+            Call::Nth(1),
+            Call::Nth(2),
+            Call::NthBack(1),
+            Call::NthBack(2),
+        ];
+
+        let slice = [3, 1, 4, 1, 5, 9, 2];
+        for len in 0..=slice.len() {
+            let hasher = build(&slice[..len]);
+            for size in 1..=4 {
+                let expected: Vec<_> = slice[..len]
+                    .windows(size)
+                    .map(|window| hasher.hash_of(window))
+                    .collect();
+                // every sequence of up to 3 calls
+                for seed in 0..calls.len().pow(3) {
+                    let mut windows = hasher.windows(size);
+                    let (mut front, mut back) = (0, expected.len());
+                    let mut seed = seed;
+                    for _ in 0..3 {
+                        let call = calls[seed % calls.len()];
+                        seed /= calls.len();
+                        let (item, model) = match call {
+                            Call::Next | Call::Nth(_) => {
+                                let n = if let Call::Nth(n) = call { n } else { 0 };
+                                let item = if n == 0 {
+                                    windows.next()
+                                } else {
+                                    windows.nth(n)
+                                };
+                                front = (front + n).min(back);
+                                let model = (front < back).then(|| expected[front]);
+                                front = (front + 1).min(back);
+                                (item, model)
+                            }
+                            Call::NextBack | Call::NthBack(_) => {
+                                let n = if let Call::NthBack(n) = call { n } else { 0 };
+                                let item = if n == 0 {
+                                    windows.next_back()
+                                } else {
+                                    windows.nth_back(n)
+                                };
+                                back = back.saturating_sub(n).max(front);
+                                let model = (front < back).then(|| expected[back - 1]);
+                                back = back.saturating_sub(1).max(front);
+                                (item, model)
+                            }
+                        };
+                        assert_eq!(item, model, "{call:?} with len = {len}, size = {size}");
+                        assert_eq!(windows.len(), back - front);
+                        assert_eq!(windows.is_empty(), front == back);
+                        assert_eq!(windows.clone().count(), back - front);
+                        assert_eq!(windows.clone().collect::<Vec<_>>(), expected[front..back]);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn next_back_after_next_with_one_window_left() {
+        // regression: `next_back` ignored the consumed front when exactly one window remained
+        let hasher = build(&[3, 1, 4]);
+        let mut windows = hasher.windows(2);
+        assert_eq!(windows.next(), Some(hasher.hash_of(&[3, 1])));
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows.next_back(), Some(hasher.hash_of(&[1, 4])));
+        assert!(windows.is_empty());
+        assert_eq!(windows.next_back(), None);
+    }
 }
@@ -0,0 +1,16 @@
+use rolling_hash::{PRIMES, RollingHash};
+
+#[test]
+fn multi_prime_hasher_is_usable_downstream() {
+    let mut hasher = RollingHash::<2>::with_config([PRIMES[9], PRIMES[8]], [31, 37]);
+    hasher.append(&mut b"abracadabra".iter().map(|&b| b as u64).collect());
+    assert_eq!(hasher.len(), 11);
+
+    let abra: Vec<u64> = b"abra".iter().map(|&b| b as u64).collect();
+    assert_eq!(hasher.find(&abra), Some(0));
+    assert_eq!(hasher.find_all(&abra), [0, 7]);
+    assert!(hasher.substring_eq(0..4, 7..11));
+
+    let other = RollingHash::<3>::from_slice(&abra);
+    assert_eq!(other.find(&abra[1..]), Some(1));
+}
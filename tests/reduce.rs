@@ -0,0 +1,26 @@
+use rolling_hash::{OneWay, Reduce};
+
+const P: u64 = (1 << 61) - 1;
+
+#[test]
+fn reduce_is_usable_downstream() {
+    let unsigned = Reduce::<P>::reduce(&(P + 3));
+    let signed = Reduce::<P>::reduce(&-1i32);
+    let word = Reduce::<P>::reduce("word");
+    assert_eq!(unsigned, 3);
+    assert_eq!(signed, P - 1);
+    assert!(word < P);
+
+    let mut hasher = OneWay::<P, 2>::with_base([1_000_003, 998_244_353]);
+    for residue in [unsigned, signed, word] {
+        hasher.push(residue);
+    }
+    assert_eq!(
+        hasher.fingerprint(),
+        hasher.hash_of(&[unsigned, signed, word])
+    );
+    // residues hash like the values they were reduced from
+    assert_eq!(hasher.hash_of(&[unsigned]), hasher.hash_of(&[P + 3]));
+    assert_eq!(hasher.hash_of(&[signed]), hasher.hash_of(&[-1i32]));
+    assert_eq!(hasher.hash_of(&[word]), hasher.hash_of(&["word"]));
+}
@@ -0,0 +1,6 @@
+use rolling_hash::Prime;
+
+// 2^61 - 31 is a prime, but DIFF = 31 exceeds 2^min(64 - 61, floor(61 / 2)) = 8
+const _: u64 = Prime::<{ (1 << 61) - 31 }>::mul_mod(2, 3);
+
+fn main() {}
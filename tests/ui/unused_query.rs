@@ -0,0 +1,9 @@
+#![deny(unused_must_use)]
+
+use rolling_hash::DefaultHasher;
+
+fn main() {
+    let hasher = DefaultHasher::from_slice(b"abcabc");
+    hasher.count(b"bc");
+    hasher.position(b"bc");
+}